@@ -40,7 +40,10 @@ GOCO_ARTIFACT="goco-$GOCO_VERSION-aarch64-linux"
 # ----------------------------
 
 # install dependencis for godot and goco
-sudo apt-get install -y git build-essential scons pkg-config clang llvm lld libsdl2-dev libgles2-mesa-dev libfontconfig libfontconfig1-dev mesa-utils vlc
+sudo apt-get install -y git build-essential scons pkg-config clang llvm lld libsdl2-dev libgles2-mesa-dev libfontconfig libfontconfig1-dev mesa-utils unclutter xdotool
+
+# allow goco to read gamepads from /dev/input
+sudo usermod -aG input $USER
 
 # download the zipped source code
 curl -LO https://github.com/godotengine/godot/archive/refs/tags/$GODOT_VERSION.zip
@@ -84,6 +87,8 @@ chmod +x ./$GOCO_ARTIFACT/bin/goco
 cp ./$GOCO_ARTIFACT/bin/goco $GOCO_ROOT/bin/goco
 # recursively copy all assets to assets folder found at known location
 cp -R ./$GOCO_ARTIFACT/assets $GOCO_ROOT/assets
+# copy the message catalogs for translating the console
+cp -R ./$GOCO_ARTIFACT/locales $GOCO_ROOT/locales
 
 
 # 4) EDIT AUTOSTART FILE TO ALLOW GOCO PROGRAM TO START-UP
@@ -101,13 +106,7 @@ echo "# This script is called by the operating system during startup.
 export GOCO_ROOT=\"$GOCO_ROOT\"
 export GOCO_GODOT_PATH=\"\$GOCO_ROOT/bin/godot.frt.opt.llvm\"
 
-# run the video on boot using VLC
-cvlc --rate=1.0 --no-video-title-show --no-osd --fullscreen \$GOCO_ROOT/boot/logo.mp4 &
-
-# wait for the animation to run before starting the goco application
-sleep 6s
-
-# start the console application
+# start the console application (it shows its own boot splash)
 \$GOCO_ROOT/bin/goco" > $GOCO_ROOT/start.sh
 
 # add the command to run the console application on start-up when rebooted
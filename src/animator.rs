@@ -1,50 +1,354 @@
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
 use iced::{Alignment, Length};
 use iced::widget::{Column, image, text, container, image::Handle};
+use ::image::{AnimationDecoder, ImageError, RgbaImage};
+use ::image::codecs::gif::GifDecoder;
+use ::image::codecs::png::PngDecoder;
 
+use crate::config::Config;
 use crate::os::Message;
 use crate::env::GOCO_ROOT;
-use std::env;
 
-#[derive(Debug, PartialEq)]
-pub struct Animation{
-    text_index: usize,
-    buf: Vec<u8>,
-}
+/// The file describing the timing of a frame sequence or the layout of a sprite sheet.
+pub const ANIMATION_FILE: &str = "animation.cfg";
+
+/// The duration of each frame when none is specified (in milliseconds).
+const DEFAULT_FRAME_MS: u64 = 100;
+
+/// The time between each change of the trailing dots in the text.
+const TEXT_PERIOD: Duration = Duration::from_millis(1000);
+
+/// The shortest time between two frames, to keep redraws within a display's refresh rate.
+const MIN_TICK: Duration = Duration::from_millis(16);
 
 const TEXT_FRAMES: usize = 4;
 
+/// How an animation continues once its last frame is shown.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LoopMode {
+    /// Stop on the last frame.
+    Once,
+    /// Start again from the first frame.
+    Loop,
+    /// Play the frames backward to the first frame, then forward again.
+    PingPong,
+}
+
+impl LoopMode {
+    /// Converts the name of a loop mode as written in an animation file.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "once" => Some(Self::Once),
+            "loop" => Some(Self::Loop),
+            "ping-pong" => Some(Self::PingPong),
+            _ => None,
+        }
+    }
+}
+
+/// The timing of an animation's frames, independent of the images drawn.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Timeline {
+    durations: Vec<Duration>,
+    mode: LoopMode,
+    index: usize,
+    /// The time spent on the current frame.
+    elapsed: Duration,
+    /// Set while a ping-pong animation plays backward.
+    reverse: bool,
+    finished: bool,
+}
+
+impl Timeline {
+    /// Creates a new [Timeline] starting on the first frame. Each frame is shown
+    /// for at least one millisecond.
+    pub fn new(durations: Vec<Duration>, mode: LoopMode) -> Self {
+        Self {
+            durations: durations.into_iter().map(|d| d.max(Duration::from_millis(1))).collect(),
+            mode: mode,
+            index: 0,
+            elapsed: Duration::ZERO,
+            reverse: false,
+            finished: false,
+        }
+    }
+
+    /// Moves the timeline forward by `delta`.
+    ///
+    /// Returns `true` if a different frame is now shown.
+    pub fn advance(&mut self, delta: Duration) -> bool {
+        if self.durations.len() < 2 || self.finished == true {
+            return false;
+        }
+        let start = self.index;
+        // a long pause (such as while a game is running) never skips more than one pass
+        self.elapsed += delta.min(self.durations.iter().sum());
+        while self.elapsed >= self.durations[self.index] {
+            self.elapsed -= self.durations[self.index];
+            if self.step() == false {
+                self.finished = true;
+                self.elapsed = Duration::ZERO;
+                break;
+            }
+        }
+        start != self.index
+    }
+
+    /// Moves to the next frame according to the loop mode.
+    ///
+    /// Returns `false` if the animation has ended.
+    fn step(&mut self) -> bool {
+        let last = self.durations.len() - 1;
+        match self.mode {
+            LoopMode::Once => {
+                if self.index == last {
+                    return false;
+                }
+                self.index += 1;
+            }
+            LoopMode::Loop => self.index = (self.index + 1) % self.durations.len(),
+            LoopMode::PingPong => {
+                if (self.reverse == true && self.index == 0) || (self.reverse == false && self.index == last) {
+                    self.reverse = !self.reverse;
+                }
+                match self.reverse {
+                    true => self.index -= 1,
+                    false => self.index += 1,
+                }
+            }
+        }
+        true
+    }
+
+    /// Accesses the index of the frame currently shown.
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Accesses the time each frame is shown.
+    pub fn get_durations(&self) -> &Vec<Duration> {
+        &self.durations
+    }
+
+    #[cfg(test)]
+    pub fn get_mode(&self) -> LoopMode {
+        self.mode
+    }
+
+    /// Checks if a [LoopMode::Once] animation has reached its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// An image animation played alongside text with cycling trailing dots.
+///
+/// The frames can be loaded from a directory of numbered images, a sprite sheet,
+/// an animated GIF or APNG, or a single still image.
+#[derive(Debug)]
+pub struct Animation {
+    text_index: usize,
+    text_elapsed: Duration,
+    frames: Vec<Handle>,
+    timeline: Timeline,
+    /// The time of the previous tick.
+    last: Option<Instant>,
+}
+
 impl Animation {
+    /// Creates a new [Animation] displaying the animation at `art`.
+    ///
+    /// The console's included `assets/insert` frames are displayed when no `art`
+    /// is provided, or `assets/insert.png` if the frames are missing.
+    pub fn new(art: Option<&PathBuf>) -> Self {
+        let assets = PathBuf::from(env::var_os(GOCO_ROOT).unwrap_or(".".into())).join("assets");
+        let path = match art {
+            Some(p) => p.clone(),
+            None => match assets.join("insert").is_dir() {
+                true => assets.join("insert"),
+                false => assets.join("insert.png"),
+            },
+        };
+        match Self::load(&path) {
+            Ok(a) => a,
+            Err(e) => {
+                log::error!("Failed to load animation: {}", e);
+                Self::from_frames(vec![Handle::from_memory(Vec::new())], Timeline::new(vec![Duration::ZERO], LoopMode::Once))
+            }
+        }
+    }
+
+    /// Reads the animation at `path`.
+    ///
+    /// A directory is read as a frame sequence (or a sprite sheet) described by
+    /// its optional `animation.cfg` file. A `.cfg` file describes a sprite sheet
+    /// or frame sequence in its own directory. A `.gif` file or `.png` file is
+    /// read as an animated image.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if path.is_dir() == true {
+            return Self::from_description(&path.join(ANIMATION_FILE), path);
+        }
+        let err = |e: ImageError| format!("{:?}: {}", path, e);
+        match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+            "cfg" => Self::from_description(path, path.parent().unwrap_or(Path::new("."))),
+            "gif" => {
+                let decoder = GifDecoder::new(Self::open(path)?).map_err(err)?;
+                Self::from_decoder(decoder).map_err(err)
+            }
+            _ => {
+                let decoder = PngDecoder::new(Self::open(path)?).map_err(err)?;
+                match decoder.is_apng() {
+                    true => Self::from_decoder(decoder.apng()).map_err(err),
+                    false => Ok(Self::from_frames(
+                        vec![Handle::from_path(path)],
+                        Timeline::new(vec![Duration::ZERO], LoopMode::Once),
+                    )),
+                }
+            }
+        }
+    }
+
+    fn open(path: &Path) -> Result<BufReader<File>, String> {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| format!("{:?}: {}", path, e))
+    }
 
-    pub fn new() -> Self {
-        let i0 = std::fs::read(format!("{}/assets/insert.png", env::var_os(GOCO_ROOT).unwrap_or(".".into()).to_string_lossy())).unwrap_or(Vec::new());
+    fn from_frames(frames: Vec<Handle>, timeline: Timeline) -> Self {
         Self {
             text_index: 0,
-            buf: i0,
+            text_elapsed: Duration::ZERO,
+            frames: frames,
+            timeline: timeline,
+            last: None,
         }
     }
 
-    pub fn get_text(&self) -> &str {
+    /// Collects the frames and their delays from an animated GIF or APNG, which
+    /// loop forever.
+    fn from_decoder<'a, D: AnimationDecoder<'a>>(decoder: D) -> Result<Self, ImageError> {
+        let (frames, durations): (Vec<Handle>, Vec<Duration>) = decoder
+            .into_frames()
+            .collect_frames()?
+            .into_iter()
+            .map(|f| {
+                let (numer, denom) = f.delay().numer_denom_ms();
+                let duration = Duration::from_millis((numer / denom.max(1)) as u64);
+                (Self::to_handle(f.into_buffer()), duration)
+            })
+            .unzip();
+        Ok(Self::from_frames(frames, Timeline::new(durations, LoopMode::Loop)))
+    }
+
+    /// Reads the animation file at `path` describing frames stored in `dir`.
+    ///
+    /// The `[animation]` table sets the default frame `duration` (in milliseconds),
+    /// the `loop` mode, and an optional sprite `sheet` split into `columns` and
+    /// `rows`. Without a sheet, every file in `dir` matching the `frames` pattern
+    /// (`*.png` by default) is a frame in name order.
+    /// The `[durations]` table sets the duration of individual frames by index.
+    fn from_description(path: &Path, dir: &Path) -> Result<Self, String> {
+        let config = Config::load(path)?;
+        let frames = match config.get("animation", "sheet") {
+            Some(sheet) => {
+                let columns = config.get("animation", "columns").and_then(|v| v.parse().ok()).unwrap_or(1);
+                let rows = config.get("animation", "rows").and_then(|v| v.parse().ok()).unwrap_or(1);
+                let count = config.get("animation", "count").and_then(|v| v.parse().ok()).unwrap_or(columns * rows);
+                Self::split_sheet(&dir.join(sheet), columns, rows, count)?
+            }
+            None => {
+                let pattern = dir.join(config.get("animation", "frames").unwrap_or("*.png"));
+                let mut paths: Vec<PathBuf> = glob::glob(&pattern.to_string_lossy())
+                    .map_err(|e| format!("{:?}: {}", path, e))?
+                    .filter_map(|p| p.ok())
+                    .collect();
+                paths.sort();
+                paths.into_iter().map(Handle::from_path).collect()
+            }
+        };
+        if frames.is_empty() == true {
+            return Err(format!("{:?}: no frames found", dir));
+        }
+        let default = config.get("animation", "duration").and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_FRAME_MS);
+        let durations = (0..frames.len())
+            .map(|i| {
+                let ms = config.get("durations", &i.to_string()).and_then(|v| v.parse().ok()).unwrap_or(default);
+                Duration::from_millis(ms)
+            })
+            .collect();
+        let mode = match config.get("animation", "loop") {
+            Some(m) => LoopMode::parse(m).ok_or(format!("{:?}: invalid loop mode {:?}", path, m))?,
+            None => LoopMode::Loop,
+        };
+        Ok(Self::from_frames(frames, Timeline::new(durations, mode)))
+    }
+
+    /// Cuts a sprite sheet into `count` frames, reading across each row of the
+    /// `columns` by `rows` grid.
+    fn split_sheet(path: &Path, columns: u32, rows: u32, count: u32) -> Result<Vec<Handle>, String> {
+        let sheet = ::image::open(path).map_err(|e| format!("{:?}: {}", path, e))?.to_rgba8();
+        let (width, height) = (sheet.width() / columns.max(1), sheet.height() / rows.max(1));
+        Ok((0..count.min(columns * rows))
+            .map(|i| {
+                let (x, y) = ((i % columns) * width, (i / columns) * height);
+                Self::to_handle(::image::imageops::crop_imm(&sheet, x, y, width, height).to_image())
+            })
+            .collect())
+    }
+
+    fn to_handle(buf: RgbaImage) -> Handle {
+        Handle::from_pixels(buf.width(), buf.height(), buf.into_raw())
+    }
+
+    /// Writes the `message` followed by the trailing dots for the current frame.
+    pub fn get_text(&self, message: &str) -> String {
         match self.text_index {
-            0 => "Please Insert GAMESTICK",
-            1 => "Please Insert GAMESTICK .",
-            2 => "Please Insert GAMESTICK . .",
-            3 => "Please Insert GAMESTICK . . .",
+            0 => message.to_string(),
+            1 => format!("{} .", message),
+            2 => format!("{} . .", message),
+            3 => format!("{} . . .", message),
             _ => panic!("unreachable text animation index: {}", self.text_index)
         }
     }
 
-    /// Updates the animator to access the next image.
-    pub fn next(&mut self) {
-        self.text_index += 1;
-        // reset the frame to replay the animation if exceeding last frame count
-        if self.text_index >= TEXT_FRAMES {
-            self.text_index = 0;
+    /// Updates the animator to the frames shown at the time `now`.
+    pub fn tick(&mut self, now: Instant) {
+        let delta = match self.last {
+            Some(last) => now.saturating_duration_since(last),
+            None => Duration::ZERO,
+        };
+        self.last = Some(now);
+        self.timeline.advance(delta);
+
+        self.text_elapsed += delta;
+        while self.text_elapsed >= TEXT_PERIOD {
+            self.text_elapsed -= TEXT_PERIOD;
+            // replay the text animation after the last frame
+            self.text_index = (self.text_index + 1) % TEXT_FRAMES;
         }
     }
 
-    /// Accesses the current frame index.
-    fn get_current_frame(&self) -> &Vec<u8> {
-        &self.buf
+    /// Computes how often the animation needs to be ticked to show every frame.
+    pub fn get_tick_rate(&self) -> Duration {
+        match self.frames.len() > 1 && self.timeline.is_finished() == false {
+            true => self.timeline.get_durations().iter().min().copied().unwrap_or(TEXT_PERIOD).clamp(MIN_TICK, TEXT_PERIOD),
+            false => TEXT_PERIOD,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn get_timeline(&self) -> &Timeline {
+        &self.timeline
+    }
+
+    /// Accesses the current frame.
+    fn get_current_frame(&self) -> &Handle {
+        &self.frames[self.timeline.get_index()]
     }
 }
 
@@ -61,10 +365,10 @@ impl<'a> Animation {
     pub fn draw(&self) -> Column<'a, Message> {
         Self::container(None)
             .push(
-                container(image(Handle::from_memory(self.get_current_frame().clone()))
+                container(image(self.get_current_frame().clone())
                 .width(Length::Fill)
                 .height(Length::Fill))
-                .center_x()         
+                .center_x()
             )
             .push(
                 text("")
@@ -73,4 +377,57 @@ impl<'a> Animation {
             )
             .align_items(Alignment::Center)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(v: u64) -> Duration {
+        Duration::from_millis(v)
+    }
+
+    #[test]
+    fn ut_timeline_loop() {
+        let mut tl = Timeline::new(vec![ms(100), ms(50), ms(50)], LoopMode::Loop);
+        assert_eq!(tl.advance(ms(99)), false);
+        assert_eq!(tl.advance(ms(1)), true);
+        assert_eq!(tl.get_index(), 1);
+        // skips across multiple frames in one step
+        tl.advance(ms(100));
+        assert_eq!(tl.get_index(), 0);
+        assert_eq!(tl.is_finished(), false);
+    }
+
+    #[test]
+    fn ut_timeline_once_and_ping_pong() {
+        let mut tl = Timeline::new(vec![ms(10); 3], LoopMode::Once);
+        tl.advance(ms(25));
+        assert_eq!(tl.get_index(), 2);
+        tl.advance(ms(10));
+        assert_eq!((tl.get_index(), tl.is_finished()), (2, true));
+
+        let mut tl = Timeline::new(vec![ms(10); 3], LoopMode::PingPong);
+        let order: Vec<usize> = (0..6).map(|_| { tl.advance(ms(10)); tl.get_index() }).collect();
+        assert_eq!(order, vec![1, 2, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn ut_load_formats() {
+        // animated gif with per-frame delays
+        let gif = Animation::load(Path::new("testenv/animations/blink.gif")).unwrap();
+        assert_eq!(gif.get_timeline().get_durations(), &vec![ms(100), ms(200), ms(300)]);
+        // sprite sheet with an incomplete last row
+        let sheet = Animation::load(Path::new("testenv/animations/sheet.cfg")).unwrap();
+        assert_eq!(sheet.get_timeline().get_durations(), &vec![ms(500), ms(50), ms(50)]);
+        assert_eq!(sheet.get_timeline().get_mode(), LoopMode::PingPong);
+        // the console's insert animation as a frame sequence
+        let seq = Animation::load(Path::new("assets/insert")).unwrap();
+        assert_eq!(seq.get_timeline().get_durations().len(), 16);
+        assert_eq!(seq.get_tick_rate(), ms(40));
+        // the console's boot splash picks its frames by pattern
+        let splash = Animation::load(Path::new("assets/boot")).unwrap();
+        assert_eq!(splash.get_timeline().get_durations().len(), 16);
+        assert_eq!(splash.get_timeline().get_mode(), LoopMode::Once);
+    }
+}
@@ -0,0 +1,300 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use iced::widget::image;
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::config::Config;
+use crate::pack::{to_io_error, PckHeader};
+
+/// The file extension for a game bundle.
+pub const BUNDLE_EXT: &str = "goco";
+
+/// The game's pack, which is required in every bundle.
+const PACK_ENTRY: &str = "game.pck";
+
+/// The icon shown for the game in the library.
+const ICON_ENTRY: &str = "icon.png";
+
+/// The wide image shown at the top of the game's details screen.
+const BANNER_ENTRY: &str = "banner.png";
+
+/// The game's manifest, written like a manifest stored next to a .pck file.
+const MANIFEST_ENTRY: &str = "manifest.cfg";
+
+/// The directory holding the screenshots shown on the game's details screen.
+const SCREENSHOTS_DIR: &str = "screenshots/";
+
+/// The directory holding the files copied into the game's save directory the
+/// first time it is played.
+const SAVE_DIR: &str = "save/";
+
+/// A game distributed as a single zip archive with the `.goco` extension,
+/// which keeps the game's files together:
+///
+/// ```text
+/// Pong.goco
+/// ├── game.pck          the game's pack (required)
+/// ├── icon.png          the icon shown in the library
+/// ├── banner.png        the banner shown on the details screen
+/// ├── manifest.cfg      the game's manifest
+/// ├── screenshots/*.png the screenshots shown on the details screen
+/// └── save/             the save template
+/// ```
+///
+/// The console reads the manifest and icon from inside the archive, and
+/// extracts the pack to the cache when the game is launched.
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    path: PathBuf,
+    manifest: Config,
+    /// The icon read along with the manifest, which is only decoded once.
+    icon: Option<image::Handle>,
+    banner: bool,
+    /// The names of the screenshots in the archive, in order.
+    screenshots: Vec<String>,
+    /// The names of the files in the save template.
+    save: Vec<String>,
+    /// The size of the pack once extracted (in bytes).
+    pack_size: u64,
+}
+
+impl PartialEq for Bundle {
+    fn eq(&self, other: &Self) -> bool {
+        // icons made from the same image data share the same id
+        self.path == other.path
+            && self.manifest == other.manifest
+            && self.icon.as_ref().map(|h| h.id()) == other.icon.as_ref().map(|h| h.id())
+            && self.banner == other.banner
+            && self.screenshots == other.screenshots
+            && self.save == other.save
+            && self.pack_size == other.pack_size
+    }
+}
+
+impl Bundle {
+    /// Reads the list of files, the manifest and the icon of the bundle at
+    /// `path`, which must hold a pack.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let mut archive = open_archive(path)?;
+        let names: Vec<String> = archive.file_names().map(|n| n.to_string()).collect();
+        let pack_size = match archive.by_name(PACK_ENTRY) {
+            Ok(entry) => entry.size(),
+            Err(ZipError::FileNotFound) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("the bundle has no {}", PACK_ENTRY),
+                ))
+            }
+            Err(e) => return Err(to_io_error(e)),
+        };
+        let manifest = match read_entry(&mut archive, MANIFEST_ENTRY)? {
+            Some(data) => Config::parse(&String::from_utf8_lossy(&data))
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{} {}", MANIFEST_ENTRY, e)))?,
+            None => Config::new(),
+        };
+        let mut screenshots: Vec<String> = names
+            .iter()
+            .filter(|n| n.starts_with(SCREENSHOTS_DIR) == true && n.ends_with(".png") == true)
+            .cloned()
+            .collect();
+        screenshots.sort();
+        Ok(Self {
+            path: path.to_path_buf(),
+            icon: read_entry(&mut archive, ICON_ENTRY)?.map(image::Handle::from_memory),
+            banner: names.iter().any(|n| n == BANNER_ENTRY),
+            screenshots: screenshots,
+            save: names
+                .iter()
+                .filter(|n| n.starts_with(SAVE_DIR) == true && n.ends_with('/') == false)
+                .cloned()
+                .collect(),
+            manifest: manifest,
+            pack_size: pack_size,
+        })
+    }
+
+    pub fn get_manifest(&self) -> &Config {
+        &self.manifest
+    }
+
+    /// Accesses the icon's image, if the bundle has an icon.
+    pub fn get_icon(&self) -> Option<&image::Handle> {
+        self.icon.as_ref()
+    }
+
+    pub fn get_pack_size(&self) -> u64 {
+        self.pack_size
+    }
+
+    pub fn get_screenshot_count(&self) -> usize {
+        self.screenshots.len()
+    }
+
+    pub fn has_banner(&self) -> bool {
+        self.banner
+    }
+
+    pub fn has_save_template(&self) -> bool {
+        self.save.is_empty() == false
+    }
+
+    /// Reads the banner's image data, if the bundle has a banner.
+    pub fn read_banner(&self) -> Option<Vec<u8>> {
+        match self.banner {
+            true => open_archive(&self.path)
+                .and_then(|mut archive| read_entry(&mut archive, BANNER_ENTRY))
+                .ok()
+                .flatten(),
+            false => None,
+        }
+    }
+
+    /// Reads the image data of up to `count` screenshots, in order.
+    pub fn read_screenshots(&self, count: usize) -> Vec<Vec<u8>> {
+        let mut archive = match open_archive(&self.path) {
+            Ok(a) => a,
+            Err(_) => return Vec::new(),
+        };
+        self.screenshots
+            .iter()
+            .take(count)
+            .filter_map(|name| read_entry(&mut archive, name).ok().flatten())
+            .collect()
+    }
+
+    /// Reads the header of the bundle's pack.
+    pub fn read_header(&self) -> std::io::Result<PckHeader> {
+        self.read_pack(|reader| PckHeader::read(&mut BufReader::new(reader)))
+    }
+
+    /// Calls `read` with a reader of the bundle's pack, which is decompressed
+    /// as it is read.
+    pub fn read_pack<T, F>(&self, read: F) -> std::io::Result<T>
+    where
+        F: FnOnce(&mut dyn Read) -> std::io::Result<T>,
+    {
+        let mut archive = open_archive(&self.path)?;
+        let mut entry = archive.by_name(PACK_ENTRY).map_err(to_io_error)?;
+        read(&mut entry)
+    }
+
+    /// Copies the save template into the game's save directory the first time
+    /// the game is played. A failure is only logged, since the game can still
+    /// start without it.
+    pub fn prepare_save(&self) {
+        let dir = match self.get_user_dir() {
+            Some(d) => d,
+            None => return,
+        };
+        match self.install_save(&dir) {
+            Ok(true) => log::info!("Copied the save template of {:?} to {:?}", self.path, dir),
+            Ok(false) => (),
+            Err(e) => log::warn!("Failed to copy the save template of {:?}: {}", self.path, e),
+        }
+    }
+
+    /// Finds the directory Godot saves the game's data to, which is named after
+    /// the `user-dir` in the manifest's `[game]` table, or else the game's name.
+    fn get_user_dir(&self) -> Option<PathBuf> {
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = self
+            .manifest
+            .get("game", "user-dir")
+            .or(self.manifest.get("game", "name"))
+            .unwrap_or(&stem);
+        Some(dirs::data_dir()?.join("godot").join("app_userdata").join(name))
+    }
+
+    /// Copies the save template into the game's save directory at `dir`,
+    /// unless the game was already played and has a save directory.
+    ///
+    /// Returns `true` if the save template was copied.
+    pub fn install_save(&self, dir: &Path) -> std::io::Result<bool> {
+        if self.has_save_template() == false || dir.exists() == true {
+            return Ok(false);
+        }
+        let mut archive = open_archive(&self.path)?;
+        for name in &self.save {
+            let mut entry = archive.by_name(name).map_err(to_io_error)?;
+            // files named outside of the archive's directories are skipped
+            let dest = match entry.enclosed_name().and_then(|p| p.strip_prefix(SAVE_DIR).ok()) {
+                Some(p) => dir.join(p),
+                None => continue,
+            };
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::io::copy(&mut entry, &mut File::create(&dest)?)?;
+        }
+        Ok(true)
+    }
+}
+
+/// Checks if the file at `path` is named like a game bundle.
+pub fn is_bundle(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == BUNDLE_EXT)
+}
+
+fn open_archive(path: &Path) -> std::io::Result<ZipArchive<BufReader<File>>> {
+    ZipArchive::new(BufReader::new(File::open(path)?)).map_err(to_io_error)
+}
+
+/// Reads the whole file `name` from the `archive`, or `None` if the archive
+/// does not have the file.
+fn read_entry(archive: &mut ZipArchive<BufReader<File>>, name: &str) -> std::io::Result<Option<Vec<u8>>> {
+    let mut entry = match archive.by_name(name) {
+        Ok(e) => e,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(to_io_error(e)),
+    };
+    let mut data = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut data)?;
+    Ok(Some(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_open_bundle() {
+        let bundle = Bundle::open(Path::new("testenv/bundles/Pong.goco")).unwrap();
+        assert_eq!(bundle.get_manifest().get("game", "version"), Some("1.1"));
+        let icon = image::Handle::from_memory(std::fs::read("testenv/GAMESTICK/Pong.png").unwrap());
+        assert_eq!(bundle.get_icon().map(|h| h.id()), Some(icon.id()));
+        assert_eq!(bundle.get_pack_size(), std::fs::metadata("testenv/GAMESTICK/Pong.pck").unwrap().len());
+        assert_eq!(bundle.read_banner().is_some(), true);
+        assert_eq!(bundle.get_screenshot_count(), 2);
+        assert_eq!(bundle.read_screenshots(1).len(), 1);
+        assert_eq!(bundle.read_header().unwrap().get_engine_version(), (3, 5, 1));
+        // the pack is decompressed as it is read
+        let pack = bundle.read_pack(|r| {
+            let mut data = Vec::new();
+            r.read_to_end(&mut data)?;
+            Ok(data)
+        });
+        assert_eq!(pack.unwrap(), std::fs::read("testenv/GAMESTICK/Pong.pck").unwrap());
+        // a bundle without a pack is not a game
+        let e = Bundle::open(Path::new("testenv/invalid/nopack.goco")).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+        let e = Bundle::open(Path::new("testenv/invalid/corrupt.pck")).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn ut_install_save_template() {
+        let dir = std::env::temp_dir().join(format!("goco-save-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let bundle = Bundle::open(Path::new("testenv/bundles/Pong.goco")).unwrap();
+        assert_eq!(bundle.install_save(&dir).unwrap(), true);
+        assert_eq!(std::fs::read_to_string(dir.join("settings.cfg")).unwrap().contains("volume = 80"), true);
+        // an existing save is never overwritten
+        std::fs::write(dir.join("settings.cfg"), "").unwrap();
+        assert_eq!(bundle.install_save(&dir).unwrap(), false);
+        assert_eq!(std::fs::read_to_string(dir.join("settings.cfg")).unwrap(), "");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,465 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::bundle::{self, Bundle};
+use crate::config::Config;
+use crate::error::GocoError;
+use crate::game::GAME_EXT;
+use crate::pack::{PackKind, ZIP_EXT};
+
+/// The directory under `GOCO_ROOT` used for the cache when no RAM disk is available.
+pub const CACHE_DIR: &str = "cache";
+
+/// The directory under the cache directory that bundles' packs are extracted
+/// to when games are not copied to RAM.
+const BUNDLES_DIR: &str = "bundles";
+
+/// The RAM disk preferred for caching games.
+const RAM_DIR: &str = "/dev/shm";
+
+/// The file remembering the content hash of each copied pack.
+const INDEX_FILE: &str = "index.tsv";
+
+/// The default size limit of the cache (in mebibytes).
+const DEFAULT_LIMIT: u64 = 512;
+
+/// The amount of data copied at a time.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The number of copies started, which names each copy's temporary file.
+static COPIES: AtomicU64 = AtomicU64::new(0);
+
+/// The amount of data copied so far, shared with the thread doing the copying.
+#[derive(Debug, Default)]
+pub struct Progress {
+    copied: AtomicU64,
+    total: AtomicU64,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Computes the fraction of the data copied so far (from 0 to 1).
+    pub fn get_fraction(&self) -> f32 {
+        match self.total.load(Ordering::SeqCst) {
+            0 => 0.0,
+            total => self.copied.load(Ordering::SeqCst) as f32 / total as f32,
+        }
+    }
+}
+
+/// A pack being copied into the cache on its own thread.
+#[derive(Debug)]
+pub struct CopyJob {
+    progress: Arc<Progress>,
+    receiver: mpsc::Receiver<Result<PathBuf, GocoError>>,
+}
+
+impl CopyJob {
+    /// Starts copying the pack at `pck` into the `cache`, or extracting it if
+    /// `pck` is a bundle.
+    pub fn start(cache: Arc<Mutex<Cache>>, pck: PathBuf) -> Self {
+        let progress = Arc::new(Progress::new());
+        let (sender, receiver) = mpsc::channel();
+        let shared = progress.clone();
+        std::thread::spawn(move || {
+            let unavailable = || GocoError::CacheFailed(pck.clone(), String::from("the cache is unavailable"));
+            // the cache is only locked while it is updated, not while the pack is copied
+            let result = cache
+                .lock()
+                .map_err(|_| unavailable())
+                .and_then(|mut locked| locked.begin(&pck))
+                .and_then(|pending| {
+                    let hash = pending.copy(&shared)?;
+                    cache.lock().map_err(|_| unavailable())?.finish(pending, hash)
+                });
+            let _ = sender.send(result);
+        });
+        Self {
+            progress: progress,
+            receiver: receiver,
+        }
+    }
+
+    pub fn get_progress(&self) -> f32 {
+        self.progress.get_fraction()
+    }
+
+    /// Checks if the copy has finished without waiting for it.
+    ///
+    /// Returns the path of the copy, or `None` while it is still being copied.
+    pub fn poll(&self) -> Option<Result<PathBuf, GocoError>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(GocoError::CacheFailed(
+                PathBuf::new(),
+                String::from("the copy stopped unexpectedly"),
+            ))),
+        }
+    }
+}
+
+/// A pack being copied into a [Cache], whose copy is stored under a temporary
+/// name until it is finished.
+struct Pending {
+    pck: PathBuf,
+    stamp: Stamp,
+    /// The bundle the pack is extracted from, if `pck` is a bundle.
+    bundle: Option<Bundle>,
+    size: u64,
+    partial: PathBuf,
+}
+
+impl Pending {
+    /// Copies the pack to its temporary name while counting the copied data
+    /// in `progress`, and returns the hash of its contents.
+    fn copy(&self, progress: &Progress) -> Result<u64, GocoError> {
+        progress.total.store(self.size, Ordering::SeqCst);
+        progress.copied.store(0, Ordering::SeqCst);
+        let result = match &self.bundle {
+            Some(b) => b.read_pack(|reader| Cache::copy(reader, &self.partial, progress)),
+            None => File::open(&self.pck).and_then(|mut reader| Cache::copy(&mut reader, &self.partial, progress)),
+        };
+        result.map_err(|e| {
+            let _ = std::fs::remove_file(&self.partial);
+            GocoError::CacheFailed(self.pck.clone(), e.to_string())
+        })
+    }
+}
+
+/// The size and modification time identifying a version of a pack.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Stamp {
+    size: u64,
+    modified: u64,
+}
+
+impl Stamp {
+    pub fn new(size: u64, modified: u64) -> Self {
+        Self {
+            size: size,
+            modified: modified,
+        }
+    }
+
+    pub fn read(path: &Path) -> std::io::Result<Self> {
+        let meta = std::fs::metadata(path)?;
+        Ok(Self {
+            size: meta.len(),
+            modified: meta
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        })
+    }
+
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn get_modified(&self) -> u64 {
+        self.modified
+    }
+}
+
+/// Copies of game packs kept on faster storage, named by the hash of their
+/// contents.
+///
+/// The least recently launched packs are removed once the cache grows past
+/// its size limit. The hash of each copied pack is remembered with its size
+/// and modification time, so unchanged packs are found without reading them.
+#[derive(Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    /// The most the cached packs may take up (in bytes).
+    limit: u64,
+    index: HashMap<PathBuf, (Stamp, u64)>,
+}
+
+impl Cache {
+    /// Opens the cache stored in `dir` holding up to `limit` bytes.
+    pub fn new(dir: &Path, limit: u64) -> Self {
+        let mut cache = Self {
+            dir: dir.to_path_buf(),
+            limit: limit,
+            index: HashMap::new(),
+        };
+        cache.load_index();
+        cache
+    }
+
+    /// Opens the cache configured by the `[cache]` table of `config`, or
+    /// returns `None` if games are launched straight from the GAMESTICK.
+    ///
+    /// The cache is stored on the RAM disk when one is available.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.get_bool("cache", "copy-to-ram") != Some(true) {
+            return None;
+        }
+        let dir = match config.get("cache", "dir") {
+            Some(d) => PathBuf::from(d),
+            None if Path::new(RAM_DIR).is_dir() == true => Path::new(RAM_DIR).join("goco"),
+            None => crate::env::root().join(CACHE_DIR),
+        };
+        Some(Self::new(&dir, Self::get_limit(config)))
+    }
+
+    /// Opens the cache that bundles' packs are extracted to when the `[cache]`
+    /// table of `config` does not copy games to RAM, stored under
+    /// `GOCO_ROOT/cache/bundles`.
+    pub fn for_bundles(config: &Config) -> Self {
+        Self::new(&crate::env::root().join(CACHE_DIR).join(BUNDLES_DIR), Self::get_limit(config))
+    }
+
+    /// Reads the cache's size limit (in bytes) from the `[cache]` table of `config`.
+    fn get_limit(config: &Config) -> u64 {
+        let limit = config
+            .get("cache", "size-limit")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_LIMIT);
+        limit * 1024 * 1024
+    }
+
+    /// Computes the path of the cached copy of the pack at `pck` with the
+    /// content hash `hash`. A zip pack keeps its extension, which the engine
+    /// needs to open it.
+    fn get_entry_path(&self, pck: &Path, hash: u64) -> PathBuf {
+        let ext = match PackKind::of(pck) {
+            PackKind::Zip => ZIP_EXT,
+            _ => GAME_EXT,
+        };
+        self.dir.join(format!("{:016x}.{}", hash, ext))
+    }
+
+    /// Finds the cached copy of the pack at `pck` if the pack has not changed
+    /// since it was copied, and marks it as the most recently used.
+    pub fn lookup(&self, pck: &Path) -> Option<PathBuf> {
+        let (stamp, hash) = self.index.get(pck)?;
+        if Stamp::read(pck).ok()? != *stamp {
+            return None;
+        }
+        let entry = self.get_entry_path(pck, *hash);
+        match File::options().append(true).open(&entry) {
+            Ok(f) => {
+                let _ = f.set_modified(SystemTime::now());
+                Some(entry)
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Copies the pack at `pck` into the cache while counting the copied data
+    /// in `progress`, and returns the path of the copy. The pack inside a
+    /// bundle is extracted instead.
+    ///
+    /// The least recently used packs are removed to make room for the copy.
+    pub fn store(&mut self, pck: &Path, progress: &Progress) -> Result<PathBuf, GocoError> {
+        let pending = self.begin(pck)?;
+        let hash = pending.copy(progress)?;
+        self.finish(pending, hash)
+    }
+
+    /// Makes room for a copy of the pack at `pck`, which is then copied with
+    /// [Pending::copy] and added to the cache with [Cache::finish].
+    fn begin(&mut self, pck: &Path) -> Result<Pending, GocoError> {
+        let fail = |reason: String| GocoError::CacheFailed(pck.to_path_buf(), reason);
+        let stamp = Stamp::read(pck).map_err(|e| fail(e.to_string()))?;
+        let bundle = match bundle::is_bundle(pck) {
+            true => Some(Bundle::open(pck).map_err(|e| fail(e.to_string()))?),
+            false => None,
+        };
+        let size = bundle.as_ref().map_or(stamp.size, |b| b.get_pack_size());
+        if size > self.limit {
+            return Err(fail(format!("the pack is larger than the cache's limit of {} bytes", self.limit)));
+        }
+        std::fs::create_dir_all(&self.dir).map_err(|e| fail(e.to_string()))?;
+        self.evict(self.limit - size);
+        // a copy left running in the background keeps its own temporary name
+        let partial = self.dir.join(format!("{}-{}.part", std::process::id(), COPIES.fetch_add(1, Ordering::SeqCst)));
+        Ok(Pending {
+            pck: pck.to_path_buf(),
+            stamp: stamp,
+            bundle: bundle,
+            size: size,
+            partial: partial,
+        })
+    }
+
+    /// Names the `pending` copy by the `hash` of its contents and remembers it.
+    fn finish(&mut self, pending: Pending, hash: u64) -> Result<PathBuf, GocoError> {
+        let pck = &pending.pck;
+        let entry = self.get_entry_path(pck, hash);
+        std::fs::rename(&pending.partial, &entry).map_err(|e| GocoError::CacheFailed(pck.clone(), e.to_string()))?;
+
+        self.index.insert(pck.clone(), (pending.stamp, hash));
+        if let Err(e) = self.save_index() {
+            log::warn!("Failed to save the cache's index: {}", e);
+        }
+        Ok(entry)
+    }
+
+    /// Copies everything read from `reader` to the file at `to` and computes
+    /// the FNV-1a hash of the contents.
+    fn copy(reader: &mut dyn Read, to: &Path, progress: &Progress) -> std::io::Result<u64> {
+        let mut writer = File::create(to)?;
+        let mut buffer = vec![0; CHUNK_SIZE];
+        let mut hash = Fnv::new();
+        loop {
+            let n = reader.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            hash.write(&buffer[..n]);
+            writer.write_all(&buffer[..n])?;
+            progress.copied.fetch_add(n as u64, Ordering::SeqCst);
+        }
+        writer.sync_all()?;
+        Ok(hash.finish())
+    }
+
+    /// Removes the least recently used packs until the cache takes up at most
+    /// `budget` bytes.
+    fn evict(&self, budget: u64) {
+        let mut entries: Vec<(PathBuf, u64, SystemTime)> = match std::fs::read_dir(&self.dir) {
+            Ok(list) => list
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().extension().map_or(false, |ext| ext == GAME_EXT || ext == ZIP_EXT))
+                .filter_map(|e| {
+                    let meta = e.metadata().ok()?;
+                    Some((e.path(), meta.len(), meta.modified().ok()?))
+                })
+                .collect(),
+            Err(_) => return,
+        };
+        // remove the oldest packs first
+        entries.sort_by_key(|(_, _, used)| *used);
+        let mut used: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        for (path, size, _) in entries {
+            if used <= budget {
+                break;
+            }
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    log::info!("Evicted {:?} from the cache", path);
+                    used -= size;
+                }
+                Err(e) => log::warn!("Failed to evict {:?} from the cache: {}", path, e),
+            }
+        }
+    }
+
+    /// Reads the remembered hashes, written one pack per line as
+    /// `hash size modified path`, separated by tabs.
+    fn load_index(&mut self) {
+        let file = match File::open(self.dir.join(INDEX_FILE)) {
+            Ok(f) => f,
+            Err(_) => return,
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            let fields: Vec<&str> = line.splitn(4, '\t').collect();
+            if let [hash, size, modified, path] = fields[..] {
+                if let (Ok(hash), Ok(size), Ok(modified)) =
+                    (u64::from_str_radix(hash, 16), size.parse(), modified.parse())
+                {
+                    self.index.insert(PathBuf::from(path), (Stamp { size: size, modified: modified }, hash));
+                }
+            }
+        }
+    }
+
+    fn save_index(&self) -> std::io::Result<()> {
+        let mut file = File::create(self.dir.join(INDEX_FILE))?;
+        for (path, (stamp, hash)) in &self.index {
+            writeln!(file, "{:016x}\t{}\t{}\t{}", hash, stamp.size, stamp.modified, path.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// The 64-bit FNV-1a hash, which is fast and needs no extra dependencies.
+#[derive(Debug)]
+pub struct Fnv(u64);
+
+impl Fnv {
+    pub fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_fnv() {
+        let mut hash = Fnv::new();
+        assert_eq!(hash.finish(), 0xcbf29ce484222325);
+        hash.write(b"a");
+        assert_eq!(hash.finish(), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn ut_store_and_evict() {
+        let dir = std::env::temp_dir().join(format!("goco-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("stick")).unwrap();
+        let packs: Vec<PathBuf> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                let path = dir.join("stick").join(format!("{}.pck", name));
+                std::fs::write(&path, name.repeat(40)).unwrap();
+                path
+            })
+            .collect();
+
+        let mut cache = Cache::new(&dir.join("cache"), 100);
+        let progress = Progress::new();
+        assert_eq!(cache.lookup(&packs[0]), None);
+        let first = cache.store(&packs[0], &progress).unwrap();
+        assert_eq!(progress.get_fraction(), 1.0);
+        assert_eq!(std::fs::read(&first).unwrap(), std::fs::read(&packs[0]).unwrap());
+        // unchanged packs are found without copying them again
+        assert_eq!(Cache::new(&dir.join("cache"), 100).lookup(&packs[0]), Some(first.clone()));
+        cache.store(&packs[1], &progress).unwrap();
+        // the least recently used pack makes room for the newest
+        cache.store(&packs[2], &progress).unwrap();
+        assert_eq!(first.exists(), false);
+        assert_eq!(cache.lookup(&packs[0]), None);
+        assert_eq!(cache.lookup(&packs[2]).is_some(), true);
+        assert_eq!(cache.store(&dir.join("stick/missing.pck"), &progress).is_err(), true);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn ut_extract_bundle() {
+        let dir = std::env::temp_dir().join(format!("goco-cache-bundle-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let bundle = PathBuf::from("testenv/bundles/Pong.goco");
+        let mut cache = Cache::new(&dir, 1024 * 1024);
+        let progress = Progress::new();
+        let pck = cache.store(&bundle, &progress).unwrap();
+        assert_eq!(progress.get_fraction(), 1.0);
+        assert_eq!(std::fs::read(&pck).unwrap(), std::fs::read("testenv/GAMESTICK/Pong.pck").unwrap());
+        assert_eq!(cache.lookup(&bundle), Some(pck));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
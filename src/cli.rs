@@ -0,0 +1,206 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use crate::env;
+
+/// The help text displayed with `--help`.
+pub const USAGE: &str = "\
+An open-source video game console for games built using the Godot game engine.
+
+Usage:
+    goco [options]              Boot the console
+    goco [options] scan <dir>   List the games found under a directory
+    goco [options] play <pck>   Launch a single game on the engine
+    goco [options] info <pck>   Display metadata about a game
+
+Options:
+    --root <dir>         Directory from where to fetch Goco-related files
+    --godot <exe>        Path to the Godot engine executable
+    --stick-path <dir>   Directory to search for the GAMESTICK drive
+    --config <file>      Path to the configuration file
+    --log-level <spec>   Levels of detail to log, such as 'info,os=debug'
+    --windowed           Disable fullscreen mode during start-up
+    --version            Print version information
+    --help               Print this help information";
+
+/// The action requested by the user from the command-line.
+#[derive(Debug, PartialEq)]
+pub enum Subcommand {
+    /// Boot the console's graphical interface.
+    Boot,
+    /// List the games found under the directory.
+    Scan(PathBuf),
+    /// Launch the game pack on the engine.
+    Play(PathBuf),
+    /// Display metadata about the game pack.
+    Info(PathBuf),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    UnknownOption(String),
+    MissingValue(String),
+    MissingArgument(&'static str, &'static str),
+    UnexpectedArgument(String),
+}
+
+impl std::error::Error for CliError {}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownOption(o) => write!(f, "unknown option '{}'", o),
+            Self::MissingValue(o) => write!(f, "option '{}' requires a value", o),
+            Self::MissingArgument(cmd, arg) => write!(f, "subcommand '{}' requires argument <{}>", cmd, arg),
+            Self::UnexpectedArgument(a) => write!(f, "unexpected argument '{}'", a),
+        }
+    }
+}
+
+/// The parsed command-line arguments.
+#[derive(Debug, PartialEq)]
+pub struct Cli {
+    pub root: Option<PathBuf>,
+    pub godot: Option<PathBuf>,
+    pub stick_path: Option<PathBuf>,
+    pub config: Option<PathBuf>,
+    pub log_level: Option<String>,
+    pub windowed: bool,
+    pub version: bool,
+    pub help: bool,
+    pub command: Subcommand,
+}
+
+impl Cli {
+    /// Parses the list of command-line arguments `args`, excluding the program name.
+    ///
+    /// Options accepting a value can be written as `--opt <value>` or `--opt=<value>`.
+    pub fn parse<I, T>(args: I) -> Result<Self, CliError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        let mut cli = Self {
+            root: None,
+            godot: None,
+            stick_path: None,
+            config: None,
+            log_level: None,
+            windowed: false,
+            version: false,
+            help: false,
+            command: Subcommand::Boot,
+        };
+        let mut positionals: Vec<String> = Vec::new();
+
+        let mut args = args.into_iter().map(|a| a.into());
+        while let Some(arg) = args.next() {
+            if arg.starts_with("--") == false {
+                positionals.push(arg);
+                continue;
+            }
+            // split an inlined value from the option's name
+            let (name, inline) = match arg.split_once('=') {
+                Some((n, v)) => (n.to_string(), Some(v.to_string())),
+                None => (arg.clone(), None),
+            };
+            match name.as_str() {
+                "--windowed" => cli.windowed = true,
+                "--version" => cli.version = true,
+                "--help" => cli.help = true,
+                "--log-level" => match inline.or_else(|| args.next()) {
+                    Some(v) => cli.log_level = Some(v),
+                    None => return Err(CliError::MissingValue(name)),
+                },
+                "--root" | "--godot" | "--stick-path" | "--config" => {
+                    let value = match inline.or_else(|| args.next()) {
+                        Some(v) => PathBuf::from(v),
+                        None => return Err(CliError::MissingValue(name)),
+                    };
+                    match name.as_str() {
+                        "--root" => cli.root = Some(value),
+                        "--godot" => cli.godot = Some(value),
+                        "--stick-path" => cli.stick_path = Some(value),
+                        _ => cli.config = Some(value),
+                    }
+                }
+                _ => return Err(CliError::UnknownOption(arg)),
+            }
+        }
+
+        let mut positionals = positionals.into_iter();
+        cli.command = match positionals.next().as_deref() {
+            None => Subcommand::Boot,
+            Some("scan") => Subcommand::Scan(Self::require(positionals.next(), "scan", "dir")?),
+            Some("play") => Subcommand::Play(Self::require(positionals.next(), "play", "pck")?),
+            Some("info") => Subcommand::Info(Self::require(positionals.next(), "info", "pck")?),
+            Some(other) => return Err(CliError::UnexpectedArgument(other.to_string())),
+        };
+        // deny any leftover arguments
+        match positionals.next() {
+            Some(extra) => Err(CliError::UnexpectedArgument(extra)),
+            None => Ok(cli),
+        }
+    }
+
+    /// Verifies a subcommand's positional argument was provided.
+    fn require(arg: Option<String>, cmd: &'static str, name: &'static str) -> Result<PathBuf, CliError> {
+        match arg {
+            Some(a) => Ok(PathBuf::from(a)),
+            None => Err(CliError::MissingArgument(cmd, name)),
+        }
+    }
+
+    /// Writes the options into their corresponding environment variables so
+    /// the command-line takes priority over the user's environment.
+    pub fn export(&self) {
+        let pairs = [
+            (env::GOCO_ROOT, &self.root),
+            (env::GOCO_GODOT_PATH, &self.godot),
+            (env::GOCO_STICK_PATH, &self.stick_path),
+            (env::GOCO_CONFIG, &self.config),
+        ];
+        for (var, value) in pairs {
+            if let Some(v) = value {
+                std::env::set_var(var, v);
+            }
+        }
+        if let Some(spec) = &self.log_level {
+            std::env::set_var(env::GOCO_LOG_LEVEL, spec);
+        }
+        if self.windowed == true {
+            std::env::set_var(env::GOCO_NO_FULLSCREEN, "1");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_parse_options() {
+        let cli = Cli::parse(["--root", "/home/pi/GOCO", "--godot=/usr/bin/godot", "--windowed", "--log-level", "debug"]).unwrap();
+        assert_eq!(cli.root, Some(PathBuf::from("/home/pi/GOCO")));
+        assert_eq!(cli.godot, Some(PathBuf::from("/usr/bin/godot")));
+        assert_eq!(cli.windowed, true);
+        assert_eq!(cli.log_level, Some(String::from("debug")));
+        assert_eq!(cli.command, Subcommand::Boot);
+    }
+
+    #[test]
+    fn ut_parse_subcommands() {
+        assert_eq!(
+            Cli::parse(["scan", "testenv/GAMESTICK"]).unwrap().command,
+            Subcommand::Scan(PathBuf::from("testenv/GAMESTICK"))
+        );
+        assert_eq!(
+            Cli::parse(["--stick-path", "/mnt", "info", "Pong.pck"]).unwrap().command,
+            Subcommand::Info(PathBuf::from("Pong.pck"))
+        );
+        assert_eq!(Cli::parse(["play"]), Err(CliError::MissingArgument("play", "pck")));
+        assert_eq!(Cli::parse(["play", "a.pck", "b.pck"]), Err(CliError::UnexpectedArgument("b.pck".to_string())));
+        assert_eq!(Cli::parse(["--config"]), Err(CliError::MissingValue("--config".to_string())));
+        assert_eq!(Cli::parse(["--fast"]), Err(CliError::UnknownOption("--fast".to_string())));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::env;
+
+/// The default file name of the configuration file found under `GOCO_ROOT`.
+pub const CONFIG_FILE: &str = "goco.cfg";
+
+/// A set of `[section]` tables holding `key = value` pairs read from a
+/// configuration file.
+///
+/// Lines beginning with `#` are comments. Keys that appear before the first
+/// section header belong to the unnamed section `""`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Config {
+    sections: BTreeMap<String, BTreeMap<String, String>>,
+    /// Set when the configuration file could not be read, so the file is never
+    /// overwritten with this configuration.
+    unreadable: bool,
+}
+
+impl Config {
+    /// Creates an empty [Config] structure.
+    pub fn new() -> Self {
+        Self {
+            sections: BTreeMap::new(),
+            unreadable: false,
+        }
+    }
+
+    /// Creates an empty [Config] standing in for a configuration file that
+    /// could not be read, which [Config::save_section] refuses to write to.
+    pub fn unreadable() -> Self {
+        Self {
+            sections: BTreeMap::new(),
+            unreadable: true,
+        }
+    }
+
+    /// Parses the contents of a configuration file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut config = Self::new();
+        let mut section = String::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            // skip blank lines and comments
+            if line.is_empty() == true || line.starts_with('#') == true {
+                continue;
+            }
+            // enter a new section
+            if line.starts_with('[') == true {
+                match line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                    Some(name) => section = name.trim().to_string(),
+                    None => return Err(format!("line {}: unterminated section header", i + 1)),
+                }
+                continue;
+            }
+            match line.split_once('=') {
+                Some((key, value)) => config.set(&section, key.trim(), value.trim()),
+                None => return Err(format!("line {}: expected 'key = value'", i + 1)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Reads and parses the configuration file at `path`.
+    ///
+    /// A missing file is treated as an empty configuration.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{:?} {}", path, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(format!("{:?}: {}", path, e)),
+        }
+    }
+
+    /// Writes the keys of the table `section` to the file at `path`, leaving
+    /// the rest of the file, including its comments, as it is.
+    ///
+    /// Keys already in the file's table are changed in place, and new keys are
+    /// added to the end of the table. A file that cannot be parsed is never
+    /// overwritten.
+    pub fn save_section(&self, path: &Path, section: &str) -> Result<(), String> {
+        if self.unreadable == true {
+            return Err(format!("{:?} was not saved because it could not be read", path));
+        }
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("{:?}: {}", path, e)),
+        };
+        Self::parse(&text).map_err(|e| format!("{:?} was not saved because it is invalid: {}", path, e))?;
+        let table = match self.sections.get(section) {
+            Some(t) => t,
+            None => return Ok(()),
+        };
+        std::fs::write(path, Self::rewrite_section(&text, section, table)).map_err(|e| format!("{:?}: {}", path, e))
+    }
+
+    /// Rewrites the valid configuration file `text` with the keys of the
+    /// table `section` set to the values in `table`.
+    fn rewrite_section(text: &str, section: &str, table: &BTreeMap<String, String>) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        let mut current = String::new();
+        let mut written: Vec<&str> = Vec::new();
+        // the line after the last key of the section, where new keys are added
+        let mut end: Option<usize> = match section.is_empty() {
+            true => Some(0),
+            false => None,
+        };
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') == true {
+                current = trimmed.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+                lines.push(line.to_string());
+                if current == section {
+                    end = Some(lines.len());
+                }
+                continue;
+            }
+            if current == section && trimmed.starts_with('#') == false {
+                if let Some((key, _)) = trimmed.split_once('=') {
+                    let key = key.trim();
+                    match table.get_key_value(key) {
+                        Some((key, value)) => {
+                            lines.push(format!("{} = {}", key, value));
+                            written.push(key);
+                        }
+                        None => lines.push(line.to_string()),
+                    }
+                    end = Some(lines.len());
+                    continue;
+                }
+            }
+            lines.push(line.to_string());
+        }
+        let missing: Vec<String> = table
+            .iter()
+            .filter(|(key, _)| written.contains(&key.as_str()) == false)
+            .map(|(key, value)| format!("{} = {}", key, value))
+            .collect();
+        match end {
+            Some(i) => {
+                lines.splice(i..i, missing);
+            }
+            None if missing.is_empty() == false => {
+                if lines.last().map_or(false, |l| l.trim().is_empty() == false) {
+                    lines.push(String::new());
+                }
+                lines.push(format!("[{}]", section));
+                lines.extend(missing);
+            }
+            None => (),
+        }
+        let mut text = lines.join("\n");
+        text.push('\n');
+        text
+    }
+
+    /// Determines the path to the configuration file.
+    ///
+    /// The `GOCO_CONFIG` environment variable takes priority over the default
+    /// location of `GOCO_ROOT/goco.cfg`.
+    pub fn default_path() -> PathBuf {
+        match std::env::var_os(env::GOCO_CONFIG) {
+            Some(p) => PathBuf::from(p),
+            None => env::root().join(CONFIG_FILE),
+        }
+    }
+
+    /// Accesses the value stored under `key` in the table `section`.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .get(section)
+            .and_then(|t| t.get(key))
+            .map(|v| v.as_str())
+    }
+
+    /// Accesses the value stored under `key` as a boolean.
+    ///
+    /// Accepts `true`/`false`, `yes`/`no`, `on`/`off`, and `1`/`0`. Any other
+    /// value is treated as missing.
+    pub fn get_bool(&self, section: &str, key: &str) -> Option<bool> {
+        match self.get(section, key)?.to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(true),
+            "false" | "no" | "off" | "0" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Accesses the value stored under `key` as a comma-separated list.
+    ///
+    /// Returns an empty list if the key is missing.
+    pub fn get_list(&self, section: &str, key: &str) -> Vec<&str> {
+        match self.get(section, key) {
+            Some(v) => v
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| s.is_empty() == false)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Accesses every `key = value` pair stored in the table `section`.
+    pub fn get_section(&self, section: &str) -> Option<&BTreeMap<String, String>> {
+        self.sections.get(section)
+    }
+
+    /// Stores `value` under `key` in the table `section`, overwriting any
+    /// previous value.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        self.sections
+            .entry(section.to_string())
+            .or_insert_with(BTreeMap::new)
+            .insert(key.to_string(), value.to_string());
+    }
+
+    /// Fills in the console's environment variables that were left unset with
+    /// their values from the `[console]` table.
+    pub fn export_defaults(&self) {
+        let pairs = [
+            (env::GOCO_GODOT_PATH, "godot"),
+            (env::GOCO_STICK_PATH, "stick-path"),
+        ];
+        for (var, key) in pairs {
+            if let Some(value) = self.get("console", key) {
+                if std::env::var_os(var).is_none() == true {
+                    std::env::set_var(var, value);
+                }
+            }
+        }
+        if self.get_bool("console", "windowed") == Some(true) {
+            std::env::set_var(env::GOCO_NO_FULLSCREEN, "1");
+        }
+    }
+}
+
+impl std::fmt::Display for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the unnamed section is sorted first and has no header
+        for (i, (name, table)) in self.sections.iter().enumerate() {
+            if name.is_empty() == false {
+                if i > 0 {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[{}]", name)?;
+            }
+            for (key, value) in table {
+                writeln!(f, "{} = {}", key, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_parse_sections() {
+        let config = Config::parse("\
+# comment
+top = 1
+
+[console]
+godot = /usr/bin/godot
+windowed = yes
+
+[library]
+dirs = /mnt/nfs, /opt/games ,
+").unwrap();
+        assert_eq!(config.get("", "top"), Some("1"));
+        assert_eq!(config.get("console", "godot"), Some("/usr/bin/godot"));
+        assert_eq!(config.get_bool("console", "windowed"), Some(true));
+        assert_eq!(config.get_list("library", "dirs"), vec!["/mnt/nfs", "/opt/games"]);
+        assert_eq!(config.get("console", "missing"), None);
+    }
+
+    #[test]
+    fn ut_display_round_trip() {
+        let mut config = Config::new();
+        config.set("display", "theme", "Ocean");
+        config.set("console", "windowed", "true");
+        config.set("", "top", "1");
+        assert_eq!(config.to_string(), "top = 1\n\n[console]\nwindowed = true\n\n[display]\ntheme = Ocean\n");
+        assert_eq!(Config::parse(&config.to_string()).unwrap(), config);
+    }
+
+    #[test]
+    fn ut_save_section() {
+        let path = std::env::temp_dir().join(format!("goco-config-{}.cfg", std::process::id()));
+        std::fs::write(&path, "\
+# console settings
+[display]
+# the console's look
+theme = Default
+scale = 2
+
+[console]
+godot = /usr/bin/godot
+").unwrap();
+        let mut config = Config::load(&path).unwrap();
+        config.set("display", "theme", "Ocean");
+        config.set("display", "language", "es");
+        config.set("console", "godot", "/opt/godot");
+        config.save_section(&path, "display").unwrap();
+        // only the section's keys change, and comments are kept
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "\
+# console settings
+[display]
+# the console's look
+theme = Ocean
+scale = 2
+language = es
+
+[console]
+godot = /usr/bin/godot
+");
+        // a missing section is added to the end
+        std::fs::write(&path, "[console]\ngodot = /usr/bin/godot\n").unwrap();
+        config.save_section(&path, "display").unwrap();
+        assert_eq!(Config::load(&path).unwrap().get("display", "theme"), Some("Ocean"));
+        assert_eq!(Config::load(&path).unwrap().get("console", "godot"), Some("/usr/bin/godot"));
+        // a file that cannot be parsed is never overwritten
+        std::fs::write(&path, "[display\ntheme = Default\n").unwrap();
+        assert_eq!(config.save_section(&path, "display").is_err(), true);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[display\ntheme = Default\n");
+        assert_eq!(Config::unreadable().save_section(&path, "display").is_err(), true);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn ut_parse_errors() {
+        assert_eq!(Config::parse("[console").is_err(), true);
+        assert_eq!(Config::parse("[console]\ngodot").is_err(), true);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,186 @@
+use iced::widget::image;
+
+use crate::game::Game;
+
+/// An action that can be taken on a game from its details screen.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Choice {
+    /// Launch the game.
+    Play,
+    /// Launch another copy of the game, by its position in [Details::get_copies].
+    PlayCopy(usize),
+    /// Copy the game from the GAMESTICK to the internal library.
+    Install,
+    /// Remove the game from the internal library.
+    Uninstall,
+    /// Close the details screen.
+    Back,
+}
+
+impl Choice {
+    /// The message catalog key for the choice's button.
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::Play => "play",
+            Self::PlayCopy(_) => "play-copy",
+            Self::Install => "install",
+            Self::Uninstall => "uninstall",
+            Self::Back => "back",
+        }
+    }
+}
+
+/// The details screen opened over the library for the selected game.
+#[derive(Debug)]
+pub struct Details {
+    game: Game,
+    /// Whether the game is stored in the internal library.
+    internal: bool,
+    /// Whether a copy of the game is stored in the internal library.
+    installed: bool,
+    /// The index of the highlighted choice.
+    cursor: usize,
+    /// Whether the game is being installed or uninstalled.
+    busy: bool,
+    /// The size of the game's files (in bytes).
+    size: u64,
+    /// The space taken by the internal library (in bytes).
+    usage: u64,
+    /// The other copies of the game found in the library.
+    copies: Vec<Game>,
+    /// The banner read from the game's bundle.
+    banner: Option<image::Handle>,
+    /// The screenshots read from the game's bundle.
+    screenshots: Vec<image::Handle>,
+}
+
+impl Details {
+    /// Creates a new [Details] screen for the `game` with its first choice highlighted.
+    pub fn new(game: Game, internal: bool, installed: bool) -> Self {
+        Self {
+            game: game,
+            internal: internal,
+            installed: installed,
+            cursor: 0,
+            busy: false,
+            size: 0,
+            usage: 0,
+            copies: Vec::new(),
+            banner: None,
+            screenshots: Vec::new(),
+        }
+    }
+
+    /// Shows the game taking `size` bytes, while the internal library takes `usage` bytes.
+    pub fn with_usage(mut self, size: u64, usage: u64) -> Self {
+        self.size = size;
+        self.usage = usage;
+        self
+    }
+
+    /// Offers to play the other `copies` of the game instead.
+    pub fn with_copies(mut self, copies: Vec<Game>) -> Self {
+        self.copies = copies;
+        self
+    }
+
+    /// Shows the `banner` and `screenshots` read from the game's bundle, whose
+    /// image data is only decoded once while the screen is open.
+    pub fn with_artwork(mut self, banner: Option<Vec<u8>>, screenshots: Vec<Vec<u8>>) -> Self {
+        self.banner = banner.map(image::Handle::from_memory);
+        self.screenshots = screenshots.into_iter().map(image::Handle::from_memory).collect();
+        self
+    }
+
+    pub fn get_game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.busy
+    }
+
+    pub fn set_busy(&mut self, busy: bool) {
+        self.busy = busy;
+    }
+
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn get_usage(&self) -> u64 {
+        self.usage
+    }
+
+    pub fn get_copies(&self) -> &Vec<Game> {
+        &self.copies
+    }
+
+    pub fn get_banner(&self) -> Option<&image::Handle> {
+        self.banner.as_ref()
+    }
+
+    pub fn get_screenshots(&self) -> &Vec<image::Handle> {
+        &self.screenshots
+    }
+
+    /// Lists the choices available for the game. Each of the game's other
+    /// copies can be played instead, a game on the GAMESTICK can be installed
+    /// once, and an installed game can be uninstalled.
+    pub fn get_choices(&self) -> Vec<Choice> {
+        let mut choices = vec![Choice::Play];
+        choices.extend((0..self.copies.len()).map(Choice::PlayCopy));
+        match (self.internal, self.installed) {
+            (true, _) => choices.push(Choice::Uninstall),
+            (false, false) => choices.push(Choice::Install),
+            (false, true) => (),
+        }
+        choices.push(Choice::Back);
+        choices
+    }
+
+    /// Highlights the previous choice, stopping at the first choice.
+    pub fn move_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Highlights the next choice, stopping at the last choice.
+    pub fn move_down(&mut self) {
+        if self.cursor + 1 < self.get_choices().len() {
+            self.cursor += 1;
+        }
+    }
+
+    /// Accesses the highlighted choice.
+    pub fn get_selected(&self) -> Choice {
+        let choices = self.get_choices();
+        choices[self.cursor.min(choices.len() - 1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn ut_choices() {
+        let game = Game::new(PathBuf::from("testenv/GAMESTICK/Pong.pck"));
+        let mut details = Details::new(game.clone(), false, false);
+        assert_eq!(details.get_choices(), vec![Choice::Play, Choice::Install, Choice::Back]);
+        details.move_down();
+        assert_eq!(details.get_selected(), Choice::Install);
+        details.move_down();
+        details.move_down();
+        assert_eq!(details.get_selected(), Choice::Back);
+        // a game that is already installed cannot be installed again
+        assert_eq!(Details::new(game.clone(), false, true).get_choices(), vec![Choice::Play, Choice::Back]);
+        assert_eq!(Details::new(game.clone(), true, true).get_choices(), vec![Choice::Play, Choice::Uninstall, Choice::Back]);
+        // other copies of the game are offered after the game itself
+        let copy = Game::new(PathBuf::from("testenv/GAMESTICK/backup/Pong.pck"));
+        assert_eq!(
+            Details::new(game, false, true).with_copies(vec![copy]).get_choices(),
+            vec![Choice::Play, Choice::PlayCopy(0), Choice::Back]
+        );
+    }
+}
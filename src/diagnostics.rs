@@ -0,0 +1,222 @@
+use std::path::{Path, PathBuf};
+
+use crate::bundle::BUNDLE_EXT;
+use crate::error::GocoError;
+use crate::game::{Game, GAME_EXT, ICON_EXT};
+use crate::pack::ZIP_EXT;
+
+/// Why a file found while reading a library was skipped or needs the
+/// attention of the drive's author.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Problem {
+    /// The file looks like a game but does not end with `.pck` or `.goco`,
+    /// such as `Pong.PCK` or `Pong.pck.bak`.
+    WrongExtension,
+    /// The file could not be read.
+    Unreadable,
+    /// The file is not a valid Godot pack.
+    CorruptPack,
+    /// The game is listed without an icon stored next to it or inside its bundle.
+    MissingIcon,
+    /// The pack was exported by a version of Godot the console cannot run.
+    UnsupportedEngine,
+}
+
+impl Problem {
+    /// The problem's name as written by `goco scan` and in a library's index.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WrongExtension => "wrong-extension",
+            Self::Unreadable => "unreadable",
+            Self::CorruptPack => "corrupt-pack",
+            Self::MissingIcon => "missing-icon",
+            Self::UnsupportedEngine => "unsupported-engine",
+        }
+    }
+
+    /// Reads a problem from its name written by [Problem::as_str].
+    pub fn parse(name: &str) -> Option<Self> {
+        [Self::WrongExtension, Self::Unreadable, Self::CorruptPack, Self::MissingIcon, Self::UnsupportedEngine]
+            .into_iter()
+            .find(|p| p.as_str() == name)
+    }
+
+    /// The message catalog key describing the problem.
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::WrongExtension => "problem-wrong-extension",
+            Self::Unreadable => "problem-unreadable",
+            Self::CorruptPack => "problem-corrupt-pack",
+            Self::MissingIcon => "problem-missing-icon",
+            Self::UnsupportedEngine => "problem-unsupported-engine",
+        }
+    }
+
+    /// Checks if a file with the problem is left out of the library.
+    pub fn is_skipped(&self) -> bool {
+        *self != Self::MissingIcon
+    }
+}
+
+/// A problem found with a file while reading a library.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Finding {
+    path: PathBuf,
+    problem: Problem,
+    /// What exactly went wrong, such as the error met while reading the file.
+    detail: String,
+}
+
+impl Finding {
+    pub fn get_path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn get_problem(&self) -> Problem {
+        self.problem
+    }
+
+    pub fn get_detail(&self) -> &str {
+        &self.detail
+    }
+}
+
+/// The problems found while reading a library, collected alongside its games
+/// so the author of a drive can find out why a game is missing.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Report {
+    findings: Vec<Finding>,
+}
+
+impl Report {
+    /// Creates an empty [Report].
+    pub fn new() -> Self {
+        Self {
+            findings: Vec::new(),
+        }
+    }
+
+    /// Records the `problem` found with the file at `path`.
+    pub fn push(&mut self, path: &Path, problem: Problem, detail: &str) {
+        self.findings.push(Finding {
+            path: path.to_path_buf(),
+            problem: problem,
+            detail: detail.to_string(),
+        });
+    }
+
+    /// Records why the file at `path` could not be read as a game from the
+    /// error `e` met while reading it.
+    pub fn push_error(&mut self, path: &Path, e: &GocoError) {
+        match e {
+            GocoError::InvalidPack(_, reason) => self.push(path, Problem::CorruptPack, reason),
+            GocoError::UnsupportedEngine(_, engine) => self.push(path, Problem::UnsupportedEngine, engine),
+            GocoError::PermissionDenied(_) => self.push(path, Problem::Unreadable, "permission denied"),
+            GocoError::MountError(_, reason) => self.push(path, Problem::Unreadable, reason),
+            _ => self.push(path, Problem::Unreadable, &e.to_string()),
+        }
+    }
+
+    /// Adds every finding of the `other` report after this report's findings.
+    pub fn append(&mut self, other: &Report) {
+        self.findings.extend(other.findings.iter().cloned());
+    }
+
+    pub fn get_findings(&self) -> &Vec<Finding> {
+        &self.findings
+    }
+
+    pub fn len(&self) -> usize {
+        self.findings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Counts the files left out of the library.
+    pub fn count_skipped(&self) -> usize {
+        self.findings.iter().filter(|f| f.problem.is_skipped() == true).count()
+    }
+}
+
+/// Checks the file at `path`, which is not named like a game, for a name that
+/// shows it was meant to be a game, reporting it to the `report`.
+pub fn check_extension(path: &Path, report: &mut Report) {
+    let name = match path.file_name() {
+        Some(n) => n.to_string_lossy().to_lowercase(),
+        None => return,
+    };
+    // games being installed are copied under a temporary name first
+    if name.ends_with(".part") == true {
+        return;
+    }
+    for ext in [GAME_EXT, ZIP_EXT, BUNDLE_EXT] {
+        if name.contains(&format!(".{}", ext)) == true && path.is_file() == true {
+            let stem = name.split(&format!(".{}", ext)).next().unwrap_or_default();
+            report.push(path, Problem::WrongExtension, &format!("rename the file to {}.{}", stem, ext));
+            return;
+        }
+    }
+}
+
+/// Checks the `game` has an icon stored next to it or inside its bundle,
+/// reporting it to the `report` otherwise.
+pub fn check_icon(game: &Game, report: &mut Report) {
+    if game.has_icon() == true {
+        return;
+    }
+    let pck = game.get_pck();
+    match game.get_bundle() {
+        Some(_) => report.push(pck, Problem::MissingIcon, "expected an icon named icon.png inside the bundle"),
+        None => {
+            let expected = pck.with_extension(ICON_EXT);
+            let name = expected.file_name().unwrap_or_default().to_string_lossy();
+            report.push(pck, Problem::MissingIcon, &format!("expected an icon named {}", name));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamestick::GameStick;
+    use crate::index::LibraryIndex;
+
+    #[test]
+    fn ut_report_skipped_files() {
+        let root = std::env::temp_dir().join(format!("goco-diagnostics-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::copy("testenv/GAMESTICK/Pong.pck", root.join("Pong.pck")).unwrap();
+        std::fs::copy("testenv/GAMESTICK/Pong.png", root.join("Pong.png")).unwrap();
+        std::fs::copy("testenv/GAMESTICK/Pong.pck", root.join("Snake.PCK")).unwrap();
+        std::fs::copy("testenv/GAMESTICK/Super Platformer.pck", root.join("Platformer.pck")).unwrap();
+        std::fs::copy("testenv/invalid/corrupt.pck", root.join("corrupt.pck")).unwrap();
+        // a pack exported by godot 2
+        let mut old = std::fs::read("testenv/GAMESTICK/Pong.pck").unwrap();
+        old[8] = 2;
+        std::fs::write(root.join("Old.pck"), &old).unwrap();
+
+        let mut report = Report::new();
+        let games = GameStick::load_indexed(&root, &mut LibraryIndex::new(), &mut report).unwrap();
+        assert_eq!(games.len(), 2);
+        let mut found: Vec<(String, Problem)> = report
+            .get_findings()
+            .iter()
+            .map(|f| (f.get_path().file_name().unwrap().to_string_lossy().into_owned(), f.get_problem()))
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            found,
+            vec![
+                (String::from("Old.pck"), Problem::UnsupportedEngine),
+                (String::from("Platformer.pck"), Problem::MissingIcon),
+                (String::from("Snake.PCK"), Problem::WrongExtension),
+                (String::from("corrupt.pck"), Problem::CorruptPack),
+            ]
+        );
+        assert_eq!(report.count_skipped(), 3);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
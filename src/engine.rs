@@ -1,14 +1,33 @@
-use std::path::Path;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use crate::env;
+use crate::error::GocoError;
 use crate::game::Game;
+use crate::pack::PackKind;
 
-#[derive(Debug, PartialEq)]
+/// The time a game is given to exit after being asked to quit before it is forced to.
+const KILL_GRACE: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
 pub struct Engine {
     /// full filepath to the Godot game engine executable
     exe: String,
-    /// the child process ID for the current game being ran on the engine
-    child: Option<u32>,
+    /// the child process for the current game being ran on the engine
+    child: Option<Child>,
+    /// set once the engine prints its first line of output for the current game
+    started: Arc<AtomicBool>,
+    /// the pack file the current game was started from
+    pack: Option<PathBuf>,
+    /// the thread quitting the last game's process
+    stopping: Option<JoinHandle<Result<(), GocoError>>>,
+    /// the current game's process is suspended
+    paused: bool,
 }
 
 impl Engine {
@@ -23,48 +42,125 @@ impl Engine {
                 Ok(val) => val,
                 Err(_) => String::from("godot"),
             },
-            child: None
+            child: None,
+            started: Arc::new(AtomicBool::new(false)),
+            pack: None,
+            stopping: None,
+            paused: false,
+        }
+    }
+
+    /// Creates a new [Engine] running its games with the executable `exe` for
+    /// testing purposes.
+    #[cfg(test)]
+    pub fn test_new(exe: &Path) -> Self {
+        Self {
+            exe: exe.display().to_string(),
+            ..Self::new()
         }
     }
 
     /// Invokes the Godot game engine and starts a [Game] `game`.
     /// 
     /// This function internally checks if a game is already running and will only
-    /// invoke a game if no child process is found.
-    pub fn play_game(&mut self, game: &Game) -> () {
-        #[cfg(not(feature = "rpi"))]
-        {
-            // check if the PID still exists (external event may have quit GODOT)
-            if self.is_in_game() == true && self.check_proc_id_alive() == false {
-                self.child = None;
-            } 
-        }
-        if self.is_in_game() == false {
-            // check if the executable exists
-            match Path::new(&self.exe).is_file() {
-                false => {
-                    eprintln!("error: Godot executable path {:?} does not exist", self.exe);
-                }
-                true => {
-                    match std::process::Command::new(&self.exe)
-                        .arg("--fullscreen")
-                        .arg("--always-on-top")
-                        .arg("--main-pack")
-                        .arg(game.get_pck())
-                        .spawn()
-                    {
-                        // store the child's ID for future usage
-                        Ok(child) => {
-                            self.child = Some(child.id());
-                        }
-                        Err(e) => {
-                            eprintln!("error: {}", e);
-                        },
-                    }
+    /// invoke a game if no child process is found. The engine's output is written
+    /// to the log at the `debug` level.
+    pub fn play_game(&mut self, game: &Game) -> Result<(), GocoError> {
+        self.play_pack(game.get_pck(), game.get_kind())
+    }
+
+    /// Invokes the Godot game engine to start the game stored in the pack file
+    /// `pck`, such as a copy of a [Game]'s pack, which is stored as the `kind`.
+    ///
+    /// A self-contained executable is run on its own instead of on the engine.
+    pub fn play_pack(&mut self, pck: &Path, kind: PackKind) -> Result<(), GocoError> {
+        // reap the last game if it exited without the console noticing (external event may have quit GODOT)
+        if let Err(e) = self.poll_game() {
+            log::warn!("{}", e);
+        }
+        if let Some(child) = &self.child {
+            return Err(GocoError::GameRunning(child.id()));
+        }
+        let mut command = match kind {
+            PackKind::Embedded => {
+                check_executable(pck)?;
+                std::process::Command::new(pck)
+            }
+            PackKind::Pck | PackKind::Zip => {
+                // check if the executable exists
+                if Path::new(&self.exe).is_file() == false {
+                    return Err(GocoError::EngineMissing(PathBuf::from(&self.exe)));
                 }
+                let mut command = std::process::Command::new(&self.exe);
+                command.arg("--main-pack").arg(pck);
+                command
             }
-        } else {
-            eprintln!("error: game is already being played on process ID {}", self.child.unwrap());
+        };
+        let program = command.get_program().to_string_lossy().into_owned();
+        let mut child = command
+            .arg("--fullscreen")
+            .arg("--always-on-top")
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| GocoError::SpawnFailed(program, e))?;
+        // the engine prints its version as soon as it starts up
+        self.started = Arc::new(AtomicBool::new(false));
+        if let Some(stdout) = child.stdout.take() {
+            let started = self.started.clone();
+            std::thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    started.store(true, Ordering::SeqCst);
+                    log::debug!("{}", line);
+                }
+            });
+        }
+        // store the child for future usage
+        self.child = Some(child);
+        self.pack = Some(pck.to_path_buf());
+        self.paused = false;
+        Ok(())
+    }
+
+    /// References the pack file the current game was started from.
+    pub fn get_pack(&self) -> Option<&PathBuf> {
+        self.child.as_ref().and(self.pack.as_ref())
+    }
+
+    /// Accesses the process ID of the current game, if a game is running.
+    pub fn get_process_id(&self) -> Option<u32> {
+        self.child.as_ref().map(|c| c.id())
+    }
+
+    /// Checks if the engine has started running the current game.
+    pub fn has_started(&self) -> bool {
+        self.is_in_game() == true && self.started.load(Ordering::SeqCst) == true
+    }
+
+    /// Checks if the currently running game has exited without waiting for it,
+    /// clearing the child process once it has.
+    /// 
+    /// Returns the game's exit status, or `None` if the game is still running.
+    pub fn poll_game(&mut self) -> Result<Option<ExitStatus>, GocoError> {
+        let status = match &mut self.child {
+            Some(child) => child.try_wait().map_err(|e| GocoError::SpawnFailed(self.exe.clone(), e))?,
+            None => return Ok(None),
+        };
+        if status.is_some() == true {
+            self.child = None;
+        }
+        Ok(status)
+    }
+
+    /// Blocks until the currently running game exits and clears the child process.
+    /// 
+    /// Returns `true` if the game exited successfully.
+    pub fn wait_game(&mut self) -> Result<bool, GocoError> {
+        match self.child.take() {
+            Some(mut child) => match child.wait() {
+                Ok(status) => Ok(status.success()),
+                Err(e) => Err(GocoError::SpawnFailed(self.exe.clone(), e)),
+            },
+            None => Ok(false),
         }
     }
 
@@ -74,62 +170,173 @@ impl Engine {
         self.child.is_some()
     }
 
-    /// Runs a subprocess to check the currently running processes if the Godot
-    /// application is still actively running.
+    /// Kills the currently owned game process and clears the child id.
     /// 
-    /// This function is only required for targets not built for the RaspberryPi.
-    #[cfg(not(feature = "rpi"))]
-    fn check_proc_id_alive(&mut self) -> bool {
-        // allow multiple instances of Godot on windows because of unavailable 'ps' command
-        if cfg!(target_os = "windows") == true {
-            return false;
-        }
-
-        if let Some(id) = self.child {
-            match std::process::Command::new("ps")
-                .arg("-p")
-                .arg(id.to_string())
-                .output()
-            {
-                // store the child's ID for future usage
-                Ok(rc) => {
-                    if rc.status.success() == true {
-                        // read the output string
-                        let text = String::from_utf8(rc.stdout).unwrap();
-                        // the process is being idle (essentially "killed")
-                        if text.contains("0:00.00") == true {
-                            false
-                        // the process is actively running
-                        } else {
-                            true
-                        }
-                    // the PID is not available to be found (essentially "killed")
-                    } else {
-                        false
-                    }
-                }
-                Err(e) => {
-                    eprintln!("error: {}", e);
-                    false
-                },
+    /// The process is quit on a separate thread, whose result is found with
+    /// [Engine::poll_stopped], so the caller is never blocked while waiting for it.
+    pub fn kill_game(&mut self) {
+        if let Some(child) = self.child.take() {
+            let exe = self.exe.clone();
+            let paused = std::mem::replace(&mut self.paused, false);
+            self.stopping = Some(std::thread::spawn(move || Self::stop(child, exe, paused)));
+        }
+    }
+
+    /// Suspends the currently running game until [Engine::resume_game] is called.
+    pub fn pause_game(&mut self) -> Result<(), GocoError> {
+        if let Some(child) = &self.child {
+            Self::signal(child, "-STOP")?;
+            self.paused = true;
+        }
+        Ok(())
+    }
+
+    /// Continues the game suspended by [Engine::pause_game].
+    pub fn resume_game(&mut self) -> Result<(), GocoError> {
+        if let Some(child) = &self.child {
+            Self::signal(child, "-CONT")?;
+            self.paused = false;
+        }
+        Ok(())
+    }
+
+    /// Checks if the current game is suspended by [Engine::pause_game].
+    pub fn is_paused(&self) -> bool {
+        self.is_in_game() == true && self.paused == true
+    }
+
+    /// Checks if the last game's process is still being quit.
+    pub fn is_stopping(&self) -> bool {
+        self.stopping.is_some()
+    }
+
+    /// Checks if the last game's process was quit without waiting for it.
+    ///
+    /// Returns the result of quitting it once, or `None` while it is still
+    /// being quit or if no game was quit.
+    pub fn poll_stopped(&mut self) -> Option<Result<(), GocoError>> {
+        if self.stopping.as_ref()?.is_finished() == false {
+            return None;
+        }
+        match self.stopping.take()?.join() {
+            Ok(result) => Some(result),
+            Err(_) => Some(Err(GocoError::SpawnFailed(
+                String::from("kill"),
+                std::io::Error::new(std::io::ErrorKind::Other, "the thread quitting the game panicked"),
+            ))),
+        }
+    }
+
+    /// Quits the `child` process started from the engine executable `exe`,
+    /// which is continued first if it was `paused`.
+    /// 
+    /// The game is first asked to quit and is forced to if it is still running
+    /// after a short time, so the process is always cleaned up.
+    fn stop(mut child: Child, exe: String, paused: bool) -> Result<(), GocoError> {
+        let result = Self::signal(&child, "-TERM");
+        // a paused game only handles the signal once it continues
+        if paused == true {
+            let _ = Self::signal(&child, "-CONT");
+        }
+        let deadline = Instant::now() + KILL_GRACE;
+        while result.is_ok() == true && Instant::now() < deadline {
+            if let Ok(Some(_)) = child.try_wait() {
+                return Ok(());
             }
-        } else {
-            false
+            std::thread::sleep(Duration::from_millis(50));
         }
+        log::warn!("Forcing the game on process ID {} to quit ...", child.id());
+        child.kill().map_err(|e| GocoError::SpawnFailed(exe, e))?;
+        let _ = child.wait();
+        Ok(())
     }
 
-    /// Kills the currently owned game process and clears the child id.
-    #[cfg(feature = "rpi")]
-    pub fn kill_game(&mut self) -> bool {
-        if self.is_in_game() == true {
-            match std::process::Command::new("kill")
-                .arg(&self.child.unwrap().to_string())
-                .spawn() 
-            {
-                Ok(_) => self.child = None,
-                Err(e) => eprintln!("error: {}", e),
-            }   
-        }
-        true
+    /// Sends the `signal` to the `child` process with the `kill` utility.
+    fn signal(child: &Child, signal: &str) -> Result<(), GocoError> {
+        match std::process::Command::new("kill")
+            .arg(signal)
+            .arg(child.id().to_string())
+            .status()
+        {
+            Ok(status) if status.success() == true => Ok(()),
+            Ok(status) => Err(GocoError::SpawnFailed(
+                String::from("kill"),
+                std::io::Error::new(std::io::ErrorKind::Other, format!("exited with {}", status)),
+            )),
+            Err(e) => Err(GocoError::SpawnFailed(String::from("kill"), e)),
+        }
+    }
+}
+
+/// Checks the self-contained executable at `path` is allowed to run, which
+/// it is not if it was copied without its permission to execute or is stored
+/// on a drive mounted without permission to run programs.
+fn check_executable(path: &Path) -> Result<(), GocoError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let metadata = std::fs::metadata(path).map_err(|e| GocoError::from_io(path.to_path_buf(), e))?;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(GocoError::NotExecutable(path.to_path_buf(), String::from("the file is not marked as executable")));
+        }
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let path = path.canonicalize().map_err(|e| GocoError::from_io(path.to_path_buf(), e))?;
+        if let Ok(mounts) = std::fs::read_to_string("/proc/self/mounts") {
+            if let Some(mount) = find_noexec_mount(&mounts, &path) {
+                return Err(GocoError::NotExecutable(path, format!("{:?} is mounted without permission to run programs", mount)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Finds the mount point holding `path` in the `mounts` table, written like
+/// `/proc/self/mounts`, if it is mounted with the `noexec` option.
+#[cfg(target_os = "linux")]
+fn find_noexec_mount(mounts: &str, path: &Path) -> Option<PathBuf> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // spaces in mount points are written as octal escapes
+            let mount = PathBuf::from(fields.get(1)?.replace("\\040", " "));
+            Some((mount, fields.get(3)?.split(',').any(|o| o == "noexec")))
+        })
+        .filter(|(mount, _)| path.starts_with(mount) == true)
+        // the last, longest mount point holding the path is the one it is on
+        .fold(None, |found: Option<(PathBuf, bool)>, (mount, noexec)| match &found {
+            Some((m, _)) if m.as_os_str().len() > mount.as_os_str().len() => found,
+            _ => Some((mount, noexec)),
+        })
+        .and_then(|(mount, noexec)| match noexec {
+            true => Some(mount),
+            false => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn ut_find_noexec_mount() {
+        let mounts = "/dev/root / ext4 rw,relatime 0 0\n\
+            tmpfs /tmp tmpfs rw,nosuid,nodev,noexec 0 0\n\
+            /dev/sda1 /media/GAME\\040STICK vfat rw,nosuid,nodev,noexec,relatime 0 0\n\
+            /dev/sdb1 /media/GAMESTICK vfat rw,nosuid,nodev,relatime 0 0\n";
+        assert_eq!(find_noexec_mount(mounts, Path::new("/media/GAME STICK/Pong.x86_64")), Some(PathBuf::from("/media/GAME STICK")));
+        assert_eq!(find_noexec_mount(mounts, Path::new("/media/GAMESTICK/Pong.x86_64")), None);
+        assert_eq!(find_noexec_mount(mounts, Path::new("/tmp/goco/Pong.x86_64")), Some(PathBuf::from("/tmp")));
+        assert_eq!(find_noexec_mount(mounts, Path::new("/home/pi/Pong.x86_64")), None);
+    }
+
+    #[test]
+    fn ut_check_executable() {
+        assert_eq!(check_executable(Path::new("testenv/exports/Pong.x86_64")).is_ok(), true);
+        // a pack is never marked as executable
+        assert_eq!(matches!(check_executable(Path::new("testenv/GAMESTICK/Pong.pck")), Err(GocoError::NotExecutable(..))), true);
     }
 }
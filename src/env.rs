@@ -6,3 +6,16 @@ pub const GOCO_NO_FULLSCREEN: &str = "GOCO_NO_FULLSCREEN";
 pub const GOCO_GODOT_PATH: &str = "GOCO_GODOT_PATH";
 /// Determine the root path from where Goco-related files are stored.
 pub const GOCO_ROOT: &str = "GOCO_ROOT";
+/// Determine the directory to search for the GAMESTICK drive.
+pub const GOCO_STICK_PATH: &str = "GOCO_STICK_PATH";
+/// Determine the path to the console's configuration file.
+pub const GOCO_CONFIG: &str = "GOCO_CONFIG";
+/// Determine the levels of detail written to the logs (such as `info,os=debug`).
+pub const GOCO_LOG_LEVEL: &str = "GOCO_LOG_LEVEL";
+
+/// Resolves the directory from where Goco-related files are stored.
+/// 
+/// Defaults to the current working directory '.' if `GOCO_ROOT` is not set.
+pub fn root() -> std::path::PathBuf {
+    std::path::PathBuf::from(std::env::var_os(GOCO_ROOT).unwrap_or(".".into()))
+}
\ No newline at end of file
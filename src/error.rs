@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::time::Duration;
+
+use crate::notify::Notice;
+
+/// The failures that can occur while running the console.
+#[derive(Debug)]
+pub enum GocoError {
+    /// The Godot executable was not found at the path.
+    EngineMissing(PathBuf),
+    /// The program could not be started or waited on.
+    SpawnFailed(String, std::io::Error),
+    /// A game is already running on the process ID.
+    GameRunning(u32),
+    /// The named game did not start on the engine within the time.
+    LaunchTimeout(String, Duration),
+    /// The named game exited with an error.
+    GameExited(String, ExitStatus),
+    /// The drive at the path could not be accessed or unmounted.
+    MountError(PathBuf, String),
+    /// The console is not allowed to access the path.
+    PermissionDenied(PathBuf),
+    /// The file at the path is not a playable Godot game.
+    InvalidPack(PathBuf, String),
+    /// The pack at the path was exported by the named engine, which the console cannot run.
+    UnsupportedEngine(PathBuf, String),
+    /// The executable at the path cannot be run for the reason.
+    NotExecutable(PathBuf, String),
+    /// The Raspberry Pi's pins could not be set up.
+    #[cfg(feature = "rpi")]
+    GpioFailure(String),
+    /// The pack at the path could not be copied into the cache.
+    CacheFailed(PathBuf, String),
+    /// A game could not be installed to or uninstalled from the internal library.
+    InstallFailed(PathBuf, String),
+}
+
+impl GocoError {
+    /// Converts an error from accessing the filesystem at `path` into a
+    /// [GocoError::PermissionDenied] or [GocoError::MountError].
+    pub fn from_io(path: PathBuf, e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied(path),
+            _ => Self::MountError(path, e.to_string()),
+        }
+    }
+
+    /// Creates the notification shown to the player for this error.
+    pub fn to_notice(&self) -> Notice {
+        match self {
+            Self::EngineMissing(p) => Notice::error("godot-missing").with("path", p.display()),
+            Self::SpawnFailed(program, e) => Notice::error("spawn-failed").with("program", program).with("reason", e),
+            Self::GameRunning(_) => Notice::warning("game-running"),
+            Self::LaunchTimeout(name, time) => Notice::error("launch-timeout").with("name", name).with("seconds", time.as_secs()),
+            Self::GameExited(name, status) => Notice::warning("game-exited").with("name", name).with("status", status),
+            Self::MountError(p, reason) => Notice::error("mount-error").with("path", p.display()).with("reason", reason),
+            Self::PermissionDenied(p) => Notice::error("permission-denied").with("path", p.display()),
+            Self::InvalidPack(p, reason) => Notice::warning("invalid-pack").with("path", p.display()).with("reason", reason),
+            Self::UnsupportedEngine(p, engine) => Notice::warning("unsupported-engine").with("path", p.display()).with("engine", engine),
+            Self::NotExecutable(p, reason) => Notice::error("not-executable").with("path", p.display()).with("reason", reason),
+            #[cfg(feature = "rpi")]
+            Self::GpioFailure(reason) => Notice::error("gpio-failed").with("reason", reason),
+            Self::CacheFailed(p, reason) => Notice::warning("cache-failed").with("path", p.display()).with("reason", reason),
+            Self::InstallFailed(p, reason) => Notice::error("install-failed").with("path", p.display()).with("reason", reason),
+        }
+    }
+}
+
+impl std::error::Error for GocoError {}
+
+impl std::fmt::Display for GocoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EngineMissing(p) => write!(f, "Godot executable path {:?} does not exist", p),
+            Self::SpawnFailed(program, e) => write!(f, "Failed to run {:?}: {}", program, e),
+            Self::GameRunning(id) => write!(f, "A game is already being played on process ID {}", id),
+            Self::LaunchTimeout(name, time) => write!(f, "Game {:?} did not start within {} seconds", name, time.as_secs()),
+            Self::GameExited(name, status) => write!(f, "Game {:?} closed unexpectedly: {}", name, status),
+            Self::MountError(p, reason) => write!(f, "Failed to access drive {:?}: {}", p, reason),
+            Self::PermissionDenied(p) => write!(f, "Permission denied for {:?}", p),
+            Self::InvalidPack(p, reason) => write!(f, "File {:?} is not a valid game: {}", p, reason),
+            Self::UnsupportedEngine(p, engine) => write!(f, "File {:?} was exported by unsupported engine {}", p, engine),
+            Self::NotExecutable(p, reason) => write!(f, "File {:?} cannot be run: {}", p, reason),
+            #[cfg(feature = "rpi")]
+            Self::GpioFailure(reason) => write!(f, "Failed to configure GPIO: {}", reason),
+            Self::CacheFailed(p, reason) => write!(f, "Failed to copy {:?} to the cache: {}", p, reason),
+            Self::InstallFailed(p, reason) => write!(f, "Failed to install {:?}: {}", p, reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_from_io() {
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        match GocoError::from_io(PathBuf::from("/media/GAMESTICK"), denied) {
+            GocoError::PermissionDenied(p) => assert_eq!(p, PathBuf::from("/media/GAMESTICK")),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        assert_eq!(GocoError::from_io(PathBuf::from("/media"), missing).to_notice().get_key(), "mount-error");
+    }
+}
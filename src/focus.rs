@@ -0,0 +1,51 @@
+use std::process::Command;
+
+/// Checks if the console is drawn on an X11 display server.
+pub fn is_x11() -> bool {
+    std::env::var_os("DISPLAY").is_some() && std::env::var_os("WAYLAND_DISPLAY").is_none()
+}
+
+/// Raises and focuses the window named `title` on a separate thread.
+///
+/// Window managers may refuse a window's own request for focus, so this asks
+/// the `xdotool` utility (or `wmctrl` when it is missing) to activate the
+/// window instead. Does nothing outside of X11.
+pub fn activate_window(title: &str) {
+    if is_x11() == false {
+        return;
+    }
+    let title = title.to_string();
+    std::thread::spawn(move || {
+        let status = Command::new("xdotool")
+            .arg("search")
+            .arg("--name")
+            .arg(format!("^{}$", title))
+            .arg("windowactivate")
+            .status()
+            .or_else(|_| Command::new("wmctrl").arg("-F").arg("-a").arg(&title).status());
+        match status {
+            Ok(s) if s.success() == true => log::debug!("Activated window {:?}", title),
+            Ok(s) => log::warn!("Failed to activate window {:?}: {}", title, s),
+            Err(e) => log::warn!("Failed to activate window {:?} with xdotool or wmctrl: {}", title, e),
+        }
+    });
+}
+
+/// Checks if the process `pid` has opened a window, using the `xdotool`
+/// utility to search for it.
+///
+/// Returns `None` if it cannot be told, such as outside of X11 or when
+/// `xdotool` is missing.
+pub fn has_window(pid: u32) -> Option<bool> {
+    if is_x11() == false {
+        return None;
+    }
+    // xdotool exits with an error when no window is found
+    match Command::new("xdotool").arg("search").arg("--pid").arg(pid.to_string()).output() {
+        Ok(output) => Some(output.status.success() == true && output.stdout.is_empty() == false),
+        Err(e) => {
+            log::debug!("Failed to search for the windows of process ID {} with xdotool: {}", pid, e);
+            None
+        }
+    }
+}
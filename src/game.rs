@@ -1,18 +1,30 @@
 use std::env;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use iced::Alignment;
+use iced::{Alignment, Color, ContentFit, Font, Length};
 use iced::widget::{Column, image, text, container};
 
+use crate::bundle::{self, Bundle, BUNDLE_EXT};
 use crate::env::GOCO_ROOT;
+use crate::error::GocoError;
+use crate::identity::Identity;
+use crate::metrics::Metrics;
+use crate::pack::{self, PackKind, PckHeader, EXECUTABLE_EXTS, ZIP_EXT};
 use crate::os::Message;
 
 
 type Pck = PathBuf;
 
-/// The default size for icons to be displayed in the game library.
-const ICON_SIZE: u16 = 256;
+/// The default size for icons to be displayed in the game library (in reference pixels).
+const ICON_SIZE: f32 = 256.0;
+
+/// The enlargement applied to the selected game's icon.
+pub const SELECTED_ZOOM: f32 = 1.5;
+
+/// The size of a game's title text (in reference pixels).
+const TITLE_SIZE: f32 = 32.0;
 
 /// The supported file extension for Godot games.
 pub const GAME_EXT: &str = "pck";
@@ -20,48 +32,197 @@ pub const GAME_EXT: &str = "pck";
 /// The supported file extension for image loading.
 pub const ICON_EXT: &str = "png";
 
-#[derive(Debug, PartialEq)]
+/// The file extension for a game's optional manifest describing the game.
+pub const MANIFEST_EXT: &str = "cfg";
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Game {
     pck: Pck,
+    name: String,
+    /// The id of the library source the game was read from.
+    source: String,
+    /// The icon stored next to the .pck file when the game was found.
+    icon: Option<PathBuf>,
+    /// What tells the game's copies apart.
+    identity: Identity,
+    /// The bundle holding the game's pack, if the game is a bundle.
+    bundle: Option<Arc<Bundle>>,
 }
 
 impl Game {
+    /// Creates a new [Game] without checking the file at `pck`.
+    /// 
+    /// Use [Game::try_from] to reject files that are not playable games.
     pub fn new(pck: Pck) -> Self {
+        let mut game = Self::indexed(pck, None);
+        game.icon = game.get_companion_path(ICON_EXT);
+        game
+    }
+
+    /// Creates a new [Game] whose `icon` was already found, such as by a
+    /// [LibraryIndex](crate::index::LibraryIndex), without checking any files.
+    pub fn indexed(pck: Pck, icon: Option<PathBuf>) -> Self {
+        let name = pck.file_stem().unwrap_or_default().to_string_lossy().into_owned();
         Self { 
+            identity: Identity::new(&name, None, None, 0),
+            name: name,
             pck: pck,
+            source: String::new(),
+            icon: icon,
+            bundle: None,
+        }
+    }
+
+    /// Creates a new [Game] like [Game::try_from], also returning the header
+    /// read from its pack.
+    pub fn read(pck: PathBuf) -> Result<(Self, PckHeader), GocoError> {
+        if Self::is_game_file(&pck) == false {
+            return Err(GocoError::InvalidPack(
+                pck,
+                format!("expected an existing .{}, .{} or .{} file, or an executable with an embedded pack", GAME_EXT, ZIP_EXT, BUNDLE_EXT),
+            ));
         }
+        if pck.file_stem().and_then(|s| s.to_str()).is_none() == true {
+            return Err(GocoError::InvalidPack(pck, String::from("the file name is not valid UTF-8")));
+        }
+        let read = match bundle::is_bundle(&pck) {
+            // a bundle's pack is read from inside the archive
+            true => Bundle::open(&pck).and_then(|b| Ok((b.read_header()?, Some(b)))),
+            false => PckHeader::from_path(&pck).map(|h| (h, None)),
+        };
+        match read {
+            Ok((header, _)) if header.is_supported() == false => {
+                let (major, minor, patch) = header.get_engine_version();
+                Err(GocoError::UnsupportedEngine(pck, format!("Godot {}.{}.{}", major, minor, patch)))
+            }
+            Ok((header, Some(bundle))) => {
+                let identity = Identity::from_manifest(&pck, bundle.get_manifest(), header.get_fingerprint());
+                Ok((Self::indexed(pck, None).with_bundle(bundle).with_identity(identity), header))
+            }
+            Ok((header, None)) => {
+                let identity = Identity::read(&pck, header.get_fingerprint());
+                Ok((Self::new(pck).with_identity(identity), header))
+            }
+            // a pack too short for its header is as broken as one with bad contents
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData || e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                Err(GocoError::InvalidPack(pck, e.to_string()))
+            }
+            Err(e) => Err(GocoError::from_io(pck, e)),
+        }
+    }
+
+    /// Remembers the game was read from the library source with the id `source`.
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = source.to_string();
+        self
+    }
+
+    /// Remembers the game's `identity`, such as one read by a
+    /// [LibraryIndex](crate::index::LibraryIndex).
+    pub fn with_identity(mut self, identity: Identity) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    pub fn get_identity(&self) -> &Identity {
+        &self.identity
+    }
+
+    /// Remembers the game's pack is stored in the `bundle`, whose icon is
+    /// shown in place of an icon stored next to it.
+    pub fn with_bundle(mut self, bundle: Bundle) -> Self {
+        self.bundle = Some(Arc::new(bundle));
+        self
+    }
+
+    /// References the bundle holding the game's pack, if the game is a bundle.
+    pub fn get_bundle(&self) -> Option<&Bundle> {
+        self.bundle.as_deref()
+    }
+
+    /// References the id of the library source the game was read from, which
+    /// is empty if the game was not read from a library source.
+    pub fn get_source(&self) -> &str {
+        &self.source
     }
 
-    /// References the game's .pck file path.
+    /// References the game's .pck file path, or the path of its bundle.
     pub fn get_pck(&self) -> &Pck {
         &self.pck
     }
 
-    /// Checks if the `path` is a valid Godot game file.
+    /// Finds how the game's pack is stored, which decides how it is launched.
+    pub fn get_kind(&self) -> PackKind {
+        PackKind::of(&self.pck)
+    }
+
+    /// Checks if the `path` is a valid Godot game file, zip pack or game
+    /// bundle, or an executable with an embedded pack.
     pub fn is_game_file<T: AsRef<Path> + ?Sized>(path: &T) -> bool {
-        std::path::Path::is_file(&path.as_ref())
-            && match path.as_ref().extension() {
-                Some(ext) => ext == GAME_EXT,
-                None => false,
-            }
+        let path = path.as_ref();
+        if std::path::Path::is_file(path) == false || Self::has_game_ext(path) == false {
+            return false;
+        }
+        // an executable is only a game if a pack was embedded in it when exported
+        match PackKind::of(path) {
+            PackKind::Embedded => pack::has_embedded_pack(path),
+            _ => true,
+        }
+    }
+
+    /// Checks if the `path` is named like a Godot game file, zip pack or game
+    /// bundle, or like an executable exported by Godot. A file without an
+    /// extension is only taken for an executable if it is marked as one, so
+    /// files such as a README are never opened.
+    pub fn has_game_ext(path: &Path) -> bool {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) => ext == GAME_EXT || ext == ZIP_EXT || ext == BUNDLE_EXT || EXECUTABLE_EXTS.contains(&ext),
+            None => is_marked_executable(path),
+        }
     }
 
     /// Attempts to extract the game's icon file path.
     /// 
-    /// If the result is some [PathBuf], then it is safe to assume the path
-    /// exists and is a file.
+    /// If the result is some [PathBuf], then the path existed and was a file
+    /// when the game was found.
     pub fn get_icon_path(&self) -> Option<PathBuf> {
-        let mut icon_path = self.pck.clone();
-        // replace the extension with the icon extension
-        icon_path.set_extension(ICON_EXT);
+        self.icon.clone()
+    }
+
+    /// Checks if the game has an icon stored next to it or inside its bundle.
+    pub fn has_icon(&self) -> bool {
+        self.icon.is_some() == true || self.get_bundle().map_or(false, |b| b.get_icon().is_some())
+    }
+
+    /// Attempts to extract the game's manifest file path, which is stored next
+    /// to the .pck file with the same name.
+    pub fn get_manifest_path(&self) -> Option<PathBuf> {
+        self.get_companion_path(MANIFEST_EXT)
+    }
+
+    /// Finds the file stored next to the game's .pck file with the same name
+    /// and the extension `ext`.
+    fn get_companion_path(&self, ext: &str) -> Option<PathBuf> {
+        let mut path = self.pck.clone();
+        // replace the extension with the companion's extension
+        path.set_extension(ext);
         // verify the path exists and is a file
-        if icon_path.exists() == true && icon_path.is_file() == true {
-            Some(icon_path)
+        if path.exists() == true && path.is_file() == true {
+            Some(path)
         } else {
             None
         }
     }
 
+    /// Lists the game's files: its .pck file followed by its icon and manifest
+    /// if they exist.
+    pub fn get_files(&self) -> Vec<PathBuf> {
+        std::iter::once(self.pck.clone())
+            .chain(self.get_icon_path())
+            .chain(self.get_manifest_path())
+            .collect()
+    }
+
     /// Returns the console's included empty icon to display when no icon is present.
     /// 
     /// Reads from the `GOCO_ROOT` environment variable to determine the base directory
@@ -71,31 +232,56 @@ impl Game {
     }
 
     pub fn get_name(&self) -> &str {
-        // @todo: provide a `from` function with Result<T,E> to ensure a loaded game has a name
-        &self.pck.file_stem().as_ref().unwrap().to_str().unwrap()
+        &self.name
+    }
+}
+
+impl TryFrom<PathBuf> for Game {
+    type Error = GocoError;
+
+    /// Creates a new [Game] after checking the file at `pck` is a Godot pack
+    /// with a readable header from a supported engine and a name written in UTF-8.
+    fn try_from(pck: PathBuf) -> Result<Self, Self::Error> {
+        Self::read(pck).map(|(game, _)| game)
     }
 }
 
 impl<'a> Game {
-    pub fn container(title: Option<&str>) -> Column<'a, Message> {
+    pub fn container(title: Option<&str>, metrics: &Metrics) -> Column<'a, Message> {
         match title {
-            Some(s) => iced::widget::column![text(s).size(50)].spacing(20),
-            None => iced::widget::column![].spacing(20)
+            Some(s) => iced::widget::column![text(s).size(metrics.scale(50.0))].spacing(metrics.scale(20.0)),
+            None => iced::widget::column![].spacing(metrics.scale(20.0))
         }
     }
 
+    /// Computes the icon's bounding size in window pixels when enlarged by `zoom`.
+    pub fn icon_size(zoom: f32, metrics: &Metrics) -> u16 {
+        metrics.scale(ICON_SIZE * zoom)
+    }
+
+    /// Assembles an icon fit within a square of `size` pixels while keeping
+    /// the image's aspect ratio.
+    fn icon<T: Into<image::Handle>>(handle: T, size: u16) -> iced::widget::Container<'a, Message> {
+        container(
+            image(handle)
+            .width(Length::Fixed(size as f32))
+            .height(Length::Shrink)
+            .content_fit(ContentFit::Contain)
+        )
+        .width(Length::Fixed(size as f32))
+        .max_height(size)
+        .center_x()
+        .center_y()
+    }
+
     /// Assembles the container to display an empty slot for a [Game] in the console's main library screen.
     /// The function returns a blank icon and no text, but in the same format as a valid game would be.
-    pub fn blank() -> Column<'a, Message> {
-        Self::container(None)
-            .push(
-                container(image(Self::empty_icon())
-                .width(ICON_SIZE)
-                .height(ICON_SIZE))
-                .center_x()         
-            )
+    pub fn blank(metrics: &Metrics) -> Column<'a, Message> {
+        Self::container(None, metrics)
+            .push(Self::icon(Self::empty_icon(), Self::icon_size(1.0, metrics)))
             .push(
                 text("")
+                .size(metrics.scale(TITLE_SIZE))
                 .vertical_alignment(iced::alignment::Vertical::Center)
                 .horizontal_alignment(iced::alignment::Horizontal::Center),
             )
@@ -103,18 +289,21 @@ impl<'a> Game {
     }
 
     /// Assembles the container to display the [Game] in the console's main library screen.
-    /// If `selected`, then the game's icon will be enlarged.
-    pub fn draw(&self, selected: bool) -> Column<'a, Message> {
-        Self::container(None)
-            .push(
-                container(
-                    image(self.get_icon_path().unwrap_or(Self::empty_icon()))
-                    .width((ICON_SIZE as f32 * if selected == true { 1.5 } else { 1.0 }) as u16)
-                    .height((ICON_SIZE as f32 * if selected == true { 1.5 } else { 1.0 }) as u16)
-                ).center_x()
-            )
+    /// The game's icon is enlarged by `zoom` (see [SELECTED_ZOOM]), and the title is
+    /// written in `font` with the color `title`.
+    pub fn draw(&self, zoom: f32, title: Color, metrics: &Metrics, font: Font) -> Column<'a, Message> {
+        let handle = match (self.get_bundle().and_then(|b| b.get_icon()), self.get_icon_path()) {
+            (Some(icon), _) => icon.clone(),
+            (None, Some(path)) => image::Handle::from_path(path),
+            (None, None) => image::Handle::from_path(Self::empty_icon()),
+        };
+        Self::container(None, metrics)
+            .push(Self::icon(handle, Self::icon_size(zoom, metrics)))
             .push(
                 text(format!("{}", self.get_name()))
+                .size(metrics.scale(TITLE_SIZE))
+                .font(font)
+                .style(title)
                 .vertical_alignment(iced::alignment::Vertical::Center)
                 .horizontal_alignment(iced::alignment::Horizontal::Center)
             )
@@ -122,6 +311,21 @@ impl<'a> Game {
     }
 }
 
+/// Checks if the file at `path` has permission to be executed, which every
+/// file has on systems without permission bits.
+fn is_marked_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path).map_or(false, |m| m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,6 +334,8 @@ mod tests {
     fn ut_is_game_file_good() {
         assert_eq!(Game::is_game_file("testenv/GAMESTICK/Super Platformer.pck"), true);
         assert_eq!(Game::is_game_file("testenv/GAMESTICK/Finite State Machine.pck"), true);
+        assert_eq!(Game::is_game_file("testenv/exports/Pong.zip"), true);
+        assert_eq!(Game::is_game_file("testenv/exports/Pong.x86_64"), true);
     }
 
     #[test]
@@ -138,15 +344,73 @@ mod tests {
         assert_eq!(Game::is_game_file("Cargo.toml"), false);
         // file does not exist
         assert_eq!(Game::is_game_file("testenv/GAMESTICK/missing.pck"), false);
+        // file without an embedded pack
         assert_eq!(Game::is_game_file("./testenv/GAMESTICK/game"), false);
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn ut_is_game_file_named() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("goco-names-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        // a mis-cased pack is left to be reported as a wrong extension
+        std::fs::copy("testenv/GAMESTICK/Pong.pck", dir.join("Pong.PCK")).unwrap();
+        assert_eq!(Game::is_game_file(&dir.join("Pong.PCK")), false);
+        let mut report = crate::diagnostics::Report::new();
+        crate::diagnostics::check_extension(&dir.join("Pong.PCK"), &mut report);
+        assert_eq!(report.get_findings()[0].get_problem(), crate::diagnostics::Problem::WrongExtension);
+        // an executable without an extension must be marked as executable
+        std::fs::copy("testenv/exports/Pong.x86_64", dir.join("Pong")).unwrap();
+        std::fs::set_permissions(dir.join("Pong"), std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert_eq!(Game::has_game_ext(&dir.join("Pong")), false);
+        std::fs::set_permissions(dir.join("Pong"), std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(Game::is_game_file(&dir.join("Pong")), true);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn ut_try_from_exports() {
+        let (game, header) = Game::read(PathBuf::from("testenv/exports/Pong.x86_64")).unwrap();
+        assert_eq!(game.get_name(), "Pong");
+        assert_eq!(game.get_kind(), PackKind::Embedded);
+        assert_eq!(header.get_engine_version(), (3, 5, 1));
+        let (game, _) = Game::read(PathBuf::from("testenv/exports/Pong.zip")).unwrap();
+        assert_eq!(game.get_kind(), PackKind::Zip);
+        // a zip that is not a main pack
+        assert_eq!(matches!(Game::try_from(PathBuf::from("testenv/invalid/notes.zip")), Err(GocoError::InvalidPack(..))), true);
+    }
+
     #[test]
     fn ut_get_icon_path_some() {
         let vg = Game::new("testenv/GAMESTICK/Finite State Machine.pck".into());
         assert_eq!(vg.get_icon_path(), Some("testenv/GAMESTICK/Finite State Machine.png".into()));
     }
 
+    #[test]
+    fn ut_try_from() {
+        let game = Game::try_from(PathBuf::from("testenv/GAMESTICK/Pong.pck")).unwrap();
+        assert_eq!(game.get_name(), "Pong");
+        // missing file
+        assert_eq!(matches!(Game::try_from(PathBuf::from("testenv/GAMESTICK/game.pck")), Err(GocoError::InvalidPack(..))), true);
+        // pack without a godot header
+        assert_eq!(matches!(Game::try_from(PathBuf::from("testenv/invalid/corrupt.pck")), Err(GocoError::InvalidPack(..))), true);
+    }
+
+    #[test]
+    fn ut_try_from_bundle() {
+        let game = Game::try_from(PathBuf::from("testenv/bundles/Pong.goco")).unwrap();
+        assert_eq!(game.get_name(), "Pong");
+        assert_eq!(game.get_icon_path(), None);
+        assert_eq!(game.has_icon(), true);
+        assert_eq!(game.get_identity().get_version(), Some("1.1"));
+        // the bundle holds the same pack as the bare game
+        let pck = Game::try_from(PathBuf::from("testenv/GAMESTICK/Pong.pck")).unwrap();
+        assert_eq!(game.get_identity().get_fingerprint(), pck.get_identity().get_fingerprint());
+        assert_eq!(matches!(Game::try_from(PathBuf::from("testenv/invalid/nopack.goco")), Err(GocoError::InvalidPack(..))), true);
+    }
+
     #[test]
     fn ut_get_icon_path_none() {
         let vg = Game::new("testenv/GAMESTICK/game.pck".into());
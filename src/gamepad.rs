@@ -0,0 +1,312 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+use crate::input::Action;
+#[cfg(target_os = "linux")]
+use crate::notify::{self, Notice};
+
+// @note: Codes follow the Linux input event codes (linux/input-event-codes.h).
+
+// event types
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+
+// buttons
+const BTN_TRIGGER: u16 = 0x120;
+const BTN_THUMB: u16 = 0x121;
+const BTN_SOUTH: u16 = 0x130;
+const BTN_EAST: u16 = 0x131;
+const BTN_NORTH: u16 = 0x133;
+const BTN_SELECT: u16 = 0x13a;
+const BTN_START: u16 = 0x13b;
+const BTN_MODE: u16 = 0x13c;
+const BTN_DPAD_UP: u16 = 0x220;
+const BTN_DPAD_DOWN: u16 = 0x221;
+const BTN_DPAD_LEFT: u16 = 0x222;
+const BTN_DPAD_RIGHT: u16 = 0x223;
+
+// axes
+const ABS_X: u16 = 0x00;
+const ABS_Y: u16 = 0x01;
+const ABS_HAT0X: u16 = 0x10;
+const ABS_HAT0Y: u16 = 0x11;
+
+/// Fraction of an analog stick's half-range that must be exceeded to register
+/// a direction.
+const STICK_DEADZONE: f32 = 0.5;
+
+/// How often to search for newly connected gamepads.
+#[cfg(target_os = "linux")]
+const HOTPLUG_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// The buttons held together to return home, which is the only input the
+/// console takes from the gamepads while a game is running.
+const HOME_CHORD: [u16; 2] = [BTN_SELECT, BTN_START];
+
+// global queue to be filled by the gamepad reader threads and drained by the main goco process
+static ACTIONS: Mutex<VecDeque<Press>> = Mutex::new(VecDeque::new());
+
+/// An action triggered on a gamepad.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Press {
+    action: Action,
+    /// Whether the action was triggered by holding the [HOME_CHORD].
+    chord: bool,
+}
+
+impl Press {
+    pub fn get_action(&self) -> Action {
+        self.action
+    }
+
+    pub fn is_chord(&self) -> bool {
+        self.chord
+    }
+}
+
+/// Converts a stream of raw input events from a single gamepad into console
+/// actions.
+#[derive(Debug, PartialEq)]
+pub struct Translator {
+    /// The (minimum, maximum) values reported by each analog stick axis.
+    ranges: HashMap<u16, (i32, i32)>,
+    /// The last direction (-1, 0, 1) registered for each axis.
+    directions: HashMap<u16, i8>,
+    /// The buttons currently held down.
+    held: HashSet<u16>,
+}
+
+impl Translator {
+    /// Creates a new [Translator] assuming signed 16-bit analog sticks.
+    pub fn new() -> Self {
+        Self {
+            ranges: HashMap::from([
+                (ABS_X, (i16::MIN as i32, i16::MAX as i32)),
+                (ABS_Y, (i16::MIN as i32, i16::MAX as i32)),
+            ]),
+            directions: HashMap::new(),
+            held: HashSet::new(),
+        }
+    }
+
+    /// Sets the (minimum, maximum) values reported by the analog stick `axis`.
+    pub fn set_range(&mut self, axis: u16, min: i32, max: i32) {
+        if max > min {
+            self.ranges.insert(axis, (min, max));
+        }
+    }
+
+    /// Translates a single input event into an action.
+    ///
+    /// Buttons trigger on press, and pressing the last button of the
+    /// [HOME_CHORD] triggers [Action::Home] instead. Axes trigger once when
+    /// entering a direction and must return to the center before triggering again.
+    pub fn translate(&mut self, kind: u16, code: u16, value: i32) -> Option<Press> {
+        let action = match kind {
+            EV_KEY => match value {
+                1 => {
+                    self.held.insert(code);
+                    if HOME_CHORD.contains(&code) == true && HOME_CHORD.iter().all(|b| self.held.contains(b)) == true {
+                        return Some(Press { action: Action::Home, chord: true });
+                    }
+                    Self::map_button(code)
+                }
+                0 => {
+                    self.held.remove(&code);
+                    None
+                }
+                // ignore auto-repeats
+                _ => None,
+            },
+            EV_ABS => {
+                let direction = match code {
+                    ABS_HAT0X | ABS_HAT0Y => value.signum() as i8,
+                    ABS_X | ABS_Y => self.stick_direction(code, value),
+                    _ => return None,
+                };
+                let previous = self.directions.insert(code, direction).unwrap_or(0);
+                if direction == previous {
+                    return None;
+                }
+                match (code, direction) {
+                    (ABS_HAT0X | ABS_X, -1) => Some(Action::Left),
+                    (ABS_HAT0X | ABS_X, 1) => Some(Action::Right),
+                    (ABS_HAT0Y | ABS_Y, -1) => Some(Action::Up),
+                    (ABS_HAT0Y | ABS_Y, 1) => Some(Action::Down),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        action.map(|a| Press { action: a, chord: false })
+    }
+
+    /// Determines the direction an analog stick axis is pushed towards.
+    fn stick_direction(&self, axis: u16, value: i32) -> i8 {
+        let (min, max) = self.ranges.get(&axis).copied().unwrap_or((-1, 1));
+        let center = (min as f32 + max as f32) / 2.0;
+        let half = (max as f32 - min as f32) / 2.0;
+        let position = (value as f32 - center) / half;
+        if position <= -STICK_DEADZONE {
+            -1
+        } else if position >= STICK_DEADZONE {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Maps a gamepad button to its console action.
+    fn map_button(code: u16) -> Option<Action> {
+        match code {
+            BTN_SOUTH | BTN_TRIGGER => Some(Action::Confirm),
+            BTN_EAST | BTN_THUMB | BTN_SELECT => Some(Action::Back),
+            BTN_START => Some(Action::Menu),
+            BTN_MODE => Some(Action::Home),
+            BTN_NORTH => Some(Action::Refresh),
+            BTN_DPAD_UP => Some(Action::Up),
+            BTN_DPAD_DOWN => Some(Action::Down),
+            BTN_DPAD_LEFT => Some(Action::Left),
+            BTN_DPAD_RIGHT => Some(Action::Right),
+            _ => None,
+        }
+    }
+}
+
+/// Removes and returns every action triggered by the gamepads since the last call.
+pub fn drain() -> Vec<Press> {
+    match ACTIONS.lock() {
+        Ok(mut queue) => queue.drain(..).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Spawns a background thread that opens gamepads as they are connected and
+/// queues their actions to be collected with [drain].
+#[cfg(target_os = "linux")]
+pub fn listen() {
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    let opened: Arc<Mutex<HashSet<PathBuf>>> = Arc::new(Mutex::new(HashSet::new()));
+    std::thread::spawn(move || loop {
+        for (path, device) in evdev::enumerate() {
+            if is_gamepad(&device) == false || opened.lock().unwrap().insert(path.clone()) == false {
+                continue;
+            }
+            let opened = opened.clone();
+            std::thread::spawn(move || {
+                read_device(device);
+                // allow the device to be opened again when it is reconnected
+                opened.lock().unwrap().remove(&path);
+            });
+        }
+        std::thread::sleep(HOTPLUG_INTERVAL);
+    });
+}
+
+/// Checks if the input device reports the buttons of a gamepad or joystick.
+#[cfg(target_os = "linux")]
+fn is_gamepad(device: &evdev::Device) -> bool {
+    match device.supported_keys() {
+        Some(keys) => keys.contains(evdev::Key::BTN_SOUTH) || keys.contains(evdev::Key::BTN_TRIGGER),
+        None => false,
+    }
+}
+
+/// Reads events from the gamepad until it is disconnected.
+#[cfg(target_os = "linux")]
+fn read_device(mut device: evdev::Device) {
+    let name = device.name().unwrap_or("unknown").to_string();
+    log::info!("Gamepad connected: {}", name);
+    notify::raise(Notice::info("gamepad-connected").with("name", &name));
+
+    let mut translator = Translator::new();
+    if let Ok(state) = device.get_abs_state() {
+        for axis in [ABS_X, ABS_Y] {
+            let info = state[axis as usize];
+            translator.set_range(axis, info.minimum, info.maximum);
+        }
+    }
+    loop {
+        match device.fetch_events() {
+            Ok(events) => {
+                for event in events {
+                    if let Some(press) = translator.translate(event.event_type().0, event.code(), event.value()) {
+                        ACTIONS.lock().unwrap().push_back(press);
+                    }
+                }
+            }
+            // the device was unplugged
+            Err(_) => break,
+        }
+    }
+    log::info!("Gamepad disconnected: {}", name);
+    notify::raise(Notice::info("gamepad-disconnected").with("name", &name));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads the (type, code, value) triples from a stream recorded with `evtest`.
+    fn read_recording(path: &str) -> Vec<(u16, u16, i32)> {
+        let field = |line: &str, name: &str| -> Option<i32> {
+            let start = line.find(name)? + name.len();
+            line[start..].split(|c: char| c == ' ' || c == ',').next()?.parse().ok()
+        };
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .filter_map(|line| {
+                Some((
+                    field(line, "type ")? as u16,
+                    field(line, "code ")? as u16,
+                    field(line, "value ")?,
+                ))
+            })
+            .collect()
+    }
+
+    fn replay(translator: &mut Translator, events: &[(u16, u16, i32)]) -> Vec<Action> {
+        events
+            .iter()
+            .filter_map(|(kind, code, value)| translator.translate(*kind, *code, *value))
+            .map(|press| press.get_action())
+            .collect()
+    }
+
+    #[test]
+    fn ut_replay_dpad_and_buttons() {
+        let events = read_recording("testenv/gamepad/dpad.evtest");
+        assert_eq!(
+            replay(&mut Translator::new(), &events),
+            vec![Action::Right, Action::Right, Action::Left, Action::Down, Action::Confirm, Action::Back, Action::Menu]
+        );
+    }
+
+    #[test]
+    fn ut_replay_analog_stick() {
+        let events = read_recording("testenv/gamepad/stick.evtest");
+        let mut translator = Translator::new();
+        translator.set_range(ABS_X, 0, 255);
+        translator.set_range(ABS_Y, 0, 255);
+        // drifting around the center triggers nothing and holding a direction triggers once
+        assert_eq!(replay(&mut translator, &events), vec![Action::Right, Action::Left, Action::Up]);
+    }
+
+    #[test]
+    fn ut_home_chord() {
+        let mut translator = Translator::new();
+        assert_eq!(translator.translate(EV_KEY, BTN_START, 1), Some(Press { action: Action::Menu, chord: false }));
+        // pressing select while start is held returns home
+        assert_eq!(translator.translate(EV_KEY, BTN_SELECT, 1), Some(Press { action: Action::Home, chord: true }));
+        assert_eq!(translator.translate(EV_KEY, BTN_SELECT, 0), None);
+        assert_eq!(translator.translate(EV_KEY, BTN_SELECT, 1), Some(Press { action: Action::Home, chord: true }));
+        assert_eq!(translator.translate(EV_KEY, BTN_START, 0), None);
+        assert_eq!(translator.translate(EV_KEY, BTN_SELECT, 0), None);
+        // the guide button alone is not the chord
+        assert_eq!(translator.translate(EV_KEY, BTN_MODE, 1), Some(Press { action: Action::Home, chord: false }));
+    }
+}
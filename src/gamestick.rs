@@ -1,55 +1,96 @@
-use crate::game;
+use crate::env;
 use crate::game::Game;
+use crate::error::GocoError;
+use crate::diagnostics::{self, Report};
+use crate::index::LibraryIndex;
+use crate::library::{LibrarySource, SourceKind};
 use std::path::PathBuf;
 
-#[derive(Debug, PartialEq)]
+/// The drive name searched for when no other name is given.
+pub const DEFAULT_LABEL: &str = "GAMESTICK";
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct GameStick {
     path: PathBuf,
+    /// The name of the drive, such as `GAMESTICK`.
+    label: String,
     library: Vec<Game>,
 }
 
 impl GameStick {
     /// Creates a new [GameStick] structure.
     pub fn new() -> Self {
+        Self::named(DEFAULT_LABEL)
+    }
+
+    /// Creates a new [GameStick] structure for a drive named `label`, such as
+    /// a second USB drive.
+    pub fn named(label: &str) -> Self {
         Self {
-            path: Self::determine_gamestick_path(),
+            path: Self::determine_gamestick_path(label),
+            label: label.to_string(),
             library: Vec::new(),
         }
     }
 
     /// Check if the operating system has permissions to read the root directory.
-    pub fn can_read_dir(&self) -> bool {
-        std::path::Path::read_dir(&self.path).is_ok()
+    pub fn check_access(&self) -> Result<(), GocoError> {
+        match std::path::Path::read_dir(&self.path) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(GocoError::from_io(self.path.clone(), e)),
+        }
     }
 
-    /// Read the [GameStick]'s filesystem for Godot game files.
+    /// Read the [GameStick]'s filesystem for Godot game files and game bundles.
     ///
     /// Assumes the [GameStick] is present and exists from the console's perspective.
-    pub fn load(root: &PathBuf) -> Vec<Game> {
-        let glob_pattern = glob::Pattern::new(&format!(
-            "{}/**/*.{}",
-            root.to_str().unwrap(),
-            game::GAME_EXT
-        ))
-        .expect("Failed to read glob pattern");
-
-        // collect all games on the drive
-        glob::glob(&glob_pattern.as_str())
-            .unwrap()
-            .filter_map(|entry| match entry {
-                Ok(path) => match Game::is_game_file(&path) {
-                    true => Some(Game::new(path)),
-                    false => None,
+    /// Files that are not playable games are skipped.
+    #[cfg(test)]
+    pub fn load(root: &PathBuf) -> Result<Vec<Game>, GocoError> {
+        Self::load_indexed(root, &mut LibraryIndex::new(), &mut Report::new())
+    }
+
+    /// Read the [GameStick]'s filesystem for Godot game files and game bundles,
+    /// only reading the files that changed since they were remembered in the `index`.
+    ///
+    /// Every file that was skipped or needs attention, such as a game without
+    /// an icon, is recorded in the `report`.
+    pub fn load_indexed(root: &PathBuf, index: &mut LibraryIndex, report: &mut Report) -> Result<Vec<Game>, GocoError> {
+        // escape the root so its characters are not mistaken for a pattern
+        let glob_pattern = format!("{}/**/*", glob::Pattern::escape(&root.to_string_lossy()));
+
+        // collect all game files on the drive, checking the other files for misnamed games
+        let mut paths: Vec<PathBuf> = Vec::new();
+        let entries = glob::glob(&glob_pattern).map_err(|e| GocoError::MountError(root.clone(), e.to_string()))?;
+        for entry in entries {
+            match entry {
+                Ok(path) if Game::is_game_file(&path) == true => paths.push(path),
+                Ok(path) => diagnostics::check_extension(&path, report),
+                Err(e) => {
+                    log::error!("{:?}", e);
+                    report.push(e.path(), diagnostics::Problem::Unreadable, &e.error().to_string());
+                }
+            }
+        }
+        let mut games = Vec::new();
+        for path in &paths {
+            match index.read(root, path) {
+                Ok(game) => {
+                    diagnostics::check_icon(&game, report);
+                    games.push(game);
                 }
                 Err(e) => {
-                    eprintln!("error: {:?}", e);
-                    None
+                    log::warn!("{}", e);
+                    report.push_error(path, &e);
                 }
-            })
-            .collect()
+            }
+        }
+        index.retain(root, &paths);
+        Ok(games)
     }
 
     /// References the root path where to search for the [GameStick].
+    #[cfg(test)]
     pub fn get_path(&self) -> &PathBuf {
         &self.path
     }
@@ -57,18 +98,22 @@ impl GameStick {
     /// Creates the base path where a bootable set of games can be found when a USB
     /// flash drive is plugged into the computer.
     ///
-    /// Supports `linux` os and `macos` os paths.
-    fn determine_gamestick_path() -> PathBuf {
-        if cfg!(target_os = "linux") == true {
+    /// Supports `linux` os and `macos` os paths for a drive named `label`. The
+    /// `GOCO_STICK_PATH` environment variable overrides the operating system's
+    /// default path for the drive named [DEFAULT_LABEL].
+    fn determine_gamestick_path(label: &str) -> PathBuf {
+        if let (Some(p), true) = (std::env::var_os(env::GOCO_STICK_PATH), label == DEFAULT_LABEL) {
+            PathBuf::from(p)
+        } else if cfg!(target_os = "linux") == true {
             let mut root = PathBuf::from("/media");
             match dirs::home_dir() {
                 Some(hp) => root.push(hp.file_name().unwrap()),
                 None => ()
             }
-            root.push("GAMESTICK");
+            root.push(label);
             root
         } else if cfg!(target_os = "macos") == true {
-            PathBuf::from("/Volumes/GAMESTICK")
+            PathBuf::from("/Volumes").join(label)
         } else if cfg!(target_os = "windows") == true {
             PathBuf::from("D:/")
         } else {
@@ -82,34 +127,59 @@ impl GameStick {
     }
 
     /// Attempts to eject the [GameStick] if the drive is available on the current filesystem.
-    ///
-    /// Returns `true` upon successfull operation and `false` upon failed operation.
-    pub fn eject(&self) -> bool {
-        if self.exists() == true {
-            #[cfg(target_os = "macos")]
-            let command = std::process::Command::new("diskutil")
-                .arg("unmount")
-                .arg(&self.path)
-                .spawn();
-            #[cfg(target_os = "linux")]
-            let command = std::process::Command::new("umount").arg(&self.path).spawn();
-            #[cfg(target_os = "windows")]
-            let command = std::process::Command::new("").spawn();
-            
-            match command {
-                // successfully ejected
-                Ok(_) => true,
-                // failed to eject
-                Err(e) => {
-                    eprintln!("error: {}", e);
-                    false
-                },
-            }
-        // not available to eject
-        } else {
-            eprintln!("error: The drive is not available to eject");
-            false
+    pub fn eject(&self) -> Result<(), GocoError> {
+        if self.exists() == false {
+            return Err(GocoError::MountError(self.path.clone(), String::from("the drive is not available to eject")));
         }
+        #[cfg(target_os = "macos")]
+        let command = std::process::Command::new("diskutil")
+            .arg("unmount")
+            .arg(&self.path)
+            .status();
+        #[cfg(target_os = "linux")]
+        let command = std::process::Command::new("umount").arg(&self.path).status();
+        #[cfg(target_os = "windows")]
+        let command = std::process::Command::new("").status();
+
+        match command {
+            // successfully ejected
+            Ok(status) if status.success() == true => Ok(()),
+            // the drive is busy or the user lacks the permission to unmount it
+            Ok(status) => Err(GocoError::MountError(self.path.clone(), format!("unmount exited with {}", status))),
+            // failed to run the unmount command
+            Err(e) => Err(GocoError::SpawnFailed(String::from("umount"), e)),
+        }
+    }
+}
+
+impl LibrarySource for GameStick {
+    fn get_id(&self) -> String {
+        format!("stick:{}", self.label)
+    }
+
+    fn get_kind(&self) -> SourceKind {
+        SourceKind::Stick
+    }
+
+    fn get_root(&self) -> &PathBuf {
+        &self.path
+    }
+
+    fn is_available(&self) -> bool {
+        self.exists()
+    }
+
+    fn is_removable(&self) -> bool {
+        true
+    }
+
+    fn read_games(&self, index: &mut LibraryIndex, report: &mut Report) -> Result<Vec<Game>, GocoError> {
+        self.check_access()?;
+        Self::load_indexed(&self.path, index, report)
+    }
+
+    fn eject(&self) -> Result<(), GocoError> {
+        GameStick::eject(self)
     }
 }
 
@@ -120,6 +190,7 @@ impl GameStick {
     pub fn test_new() -> Self {
         Self {
             path: PathBuf::from("testenv/GAMESTICK"),
+            label: String::from(DEFAULT_LABEL),
             library: Vec::new(),
         }
     }
@@ -145,7 +216,7 @@ mod tests {
 
     #[test]
     fn ut_load_library() {
-        let library = GameStick::load(GameStick::test_new().get_path());
+        let library = GameStick::load(GameStick::test_new().get_path()).unwrap();
 
         assert_eq!(library.len(), 4);
         assert_eq!(
@@ -6,6 +6,8 @@ use rppal::gpio::{OutputPin, InputPin, Trigger, Level};
 
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::error::GocoError;
+
 // @note: Gpio uses BCM pin numbering. BCM GPIO 23 is tied to physical pin 16.
 
 // input pins
@@ -107,8 +109,12 @@ impl Io {
     }
 
     /// Initializes the GPIO pins for corresponding input/output modes.
-    pub fn configure() -> Result<Self, Box<dyn Error>> {
-        println!("info: Initializing GPIO on a {} ...", DeviceInfo::new()?.model());
+    pub fn configure() -> Result<Self, GocoError> {
+        Self::try_configure().map_err(|e| GocoError::GpioFailure(e.to_string()))
+    }
+
+    fn try_configure() -> Result<Self, Box<dyn Error>> {
+        log::info!("Initializing GPIO on a {} ...", DeviceInfo::new()?.model());
         // define the interface pin directions
         let mut io = Self {
             pwr_led: Pin::Output(Gpio::new()?.get(GPIO_PWR_PIN)?.into_output()),
@@ -137,7 +143,7 @@ impl Io {
     fn eject_callback(level: Level) -> () {
         if level == Level::Low {
             IS_EJECT_TRIGGERED.store(true, Ordering::SeqCst);
-            // println!("eject button pressed!");
+            log::debug!("eject button pressed");
         }
     }
 
@@ -145,7 +151,7 @@ impl Io {
     fn power_callback(level: Level) -> () {
         if level == Level::Low {
             IS_POWER_TRIGGERED.store(true, Ordering::SeqCst);
-            // println!("power button pressed!");
+            log::debug!("power button pressed");
         }
     }
 
@@ -153,7 +159,7 @@ impl Io {
     fn home_callback(level: Level) -> () {
         if level == Level::Low {
             IS_HOME_TRIGGERED.store(true, Ordering::SeqCst);
-            // println!("home button pressed!");
+            log::debug!("home button pressed");
         }
     }
 
@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::bundle::{self, Bundle};
+use crate::config::Config;
+use crate::game::MANIFEST_EXT;
+
+/// What tells whether two games are copies of the same game, and which copy
+/// is the newest.
+///
+/// Copies are packs with the same contents, or packs of the same project. A
+/// project is named in the `[game]` table of the game's manifest, along with
+/// its version:
+///
+/// ```text
+/// [game]
+/// name = Pong
+/// version = 1.2
+/// ```
+///
+/// A game without a manifest is named after its pack's file name. A bundle's
+/// manifest is stored inside the bundle.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Identity {
+    /// The project's name from the manifest, or else the pack's file name.
+    project: String,
+    /// The project's version from the manifest.
+    version: Option<String>,
+    /// The hash of the pack's file directory, if it could be read.
+    fingerprint: Option<u64>,
+    /// When the pack was last modified (in seconds since the epoch).
+    modified: u64,
+}
+
+impl Identity {
+    pub fn new(project: &str, version: Option<&str>, fingerprint: Option<u64>, modified: u64) -> Self {
+        Self {
+            project: project.to_string(),
+            version: version.map(|v| v.to_string()),
+            fingerprint: fingerprint,
+            modified: modified,
+        }
+    }
+
+    /// Reads the identity of the pack at `pck` whose file directory hashes to
+    /// `fingerprint`, along with the manifest stored next to it or inside its
+    /// bundle.
+    pub fn read(pck: &Path, fingerprint: Option<u64>) -> Self {
+        let manifest = match bundle::is_bundle(pck) {
+            true => Bundle::open(pck).map(|b| b.get_manifest().clone()).unwrap_or_default(),
+            false => Config::load(&pck.with_extension(MANIFEST_EXT)).unwrap_or_else(|_| Config::new()),
+        };
+        Self::from_manifest(pck, &manifest, fingerprint)
+    }
+
+    /// Creates the identity of the pack at `pck` whose file directory hashes
+    /// to `fingerprint` from its `manifest`, which was already read.
+    pub fn from_manifest(pck: &Path, manifest: &Config, fingerprint: Option<u64>) -> Self {
+        let name = pck.file_stem().unwrap_or_default().to_string_lossy();
+        let modified = std::fs::metadata(pck)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+        Self::new(
+            manifest.get("game", "name").unwrap_or(&name),
+            manifest.get("game", "version"),
+            fingerprint,
+            modified,
+        )
+    }
+
+    pub fn get_project(&self) -> &str {
+        &self.project
+    }
+
+    pub fn get_version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn get_fingerprint(&self) -> Option<u64> {
+        self.fingerprint
+    }
+
+    /// Checks if the games are copies of each other: their packs have the same
+    /// contents or they belong to the same project. Project names are compared
+    /// ignoring case.
+    pub fn is_copy_of(&self, other: &Identity) -> bool {
+        (self.fingerprint.is_some() && self.fingerprint == other.fingerprint)
+            || self.project.to_lowercase() == other.project.to_lowercase()
+    }
+
+    /// Checks if the game is a newer version than the `other` copy.
+    ///
+    /// Packs with the same contents are the same version. Otherwise the
+    /// versions in the manifests are compared, or else the time each pack was
+    /// last modified.
+    pub fn is_newer_than(&self, other: &Identity) -> bool {
+        if self.fingerprint.is_some() && self.fingerprint == other.fingerprint {
+            return false;
+        }
+        let order = match (&self.version, &other.version) {
+            (Some(a), Some(b)) => compare_versions(a, b),
+            _ => Ordering::Equal,
+        };
+        match order {
+            Ordering::Equal => self.modified > other.modified,
+            _ => order == Ordering::Greater,
+        }
+    }
+}
+
+/// Compares two version numbers such as `1.10` and `1.9` part by part, where
+/// numeric parts are compared as numbers.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> Vec<String> {
+        v.trim_start_matches(['v', 'V'])
+            .split(['.', '-', '+'])
+            .map(|p| p.to_string())
+            .collect()
+    };
+    let (a, b) = (split(a), split(b));
+    for (x, y) in a.iter().zip(&b) {
+        let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_newest_copy() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("v2.0", "2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0", "1.0.1"), Ordering::Less);
+
+        let old = Identity::new("Pong", Some("1.2"), Some(1), 200);
+        let new = Identity::new("pong", Some("1.10"), Some(2), 100);
+        assert_eq!(new.is_copy_of(&old), true);
+        assert_eq!(new.is_newer_than(&old), true);
+        assert_eq!(old.is_newer_than(&new), false);
+        // without versions the most recently modified pack is the newest
+        let copy = Identity::new("Pong", None, Some(3), 300);
+        assert_eq!(copy.is_newer_than(&old), true);
+        // identical packs are the same version wherever they are found
+        let same = Identity::new("Pong (backup)", None, Some(1), 900);
+        assert_eq!(same.is_copy_of(&old), true);
+        assert_eq!(same.is_newer_than(&old), false);
+        assert_eq!(Identity::new("Snake", None, None, 0).is_copy_of(&old), false);
+    }
+}
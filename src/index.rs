@@ -0,0 +1,330 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::bundle::{self, Bundle};
+use crate::cache::{Fnv, Stamp};
+use crate::diagnostics::Problem;
+use crate::error::GocoError;
+use crate::game::{Game, ICON_EXT};
+use crate::identity::Identity;
+
+/// The directory under the console's cache directory holding the index of
+/// each library source.
+pub const INDEX_DIR: &str = "index";
+
+/// What was found in a pack file the last time it was read.
+#[derive(Debug, PartialEq, Clone)]
+struct Entry {
+    /// The size and modification time of the pack file.
+    stamp: Stamp,
+    /// The modification time of the pack's directory, which changes whenever
+    /// an icon is added next to the pack or removed.
+    dir_modified: u64,
+    /// The engine version read from the pack's header, or why the pack is not
+    /// a playable game.
+    header: Result<String, (Problem, String)>,
+    /// The icon found next to the pack, relative to the library's root.
+    icon: Option<PathBuf>,
+    /// What tells the game's copies apart, read from the pack and its manifest.
+    identity: Identity,
+}
+
+/// The games found the last time a library was read, remembered so only the
+/// packs that changed since then are read again.
+///
+/// Entries are stored relative to the library's root, so a drive's index
+/// still applies when the drive is mounted somewhere else.
+#[derive(Debug, PartialEq)]
+pub struct LibraryIndex {
+    /// The file the index is saved to, if it is saved at all.
+    file: Option<PathBuf>,
+    entries: HashMap<PathBuf, Entry>,
+    /// Whether the entries changed since they were loaded.
+    changed: bool,
+}
+
+impl LibraryIndex {
+    /// Creates an empty [LibraryIndex] that is never saved.
+    pub fn new() -> Self {
+        Self {
+            file: None,
+            entries: HashMap::new(),
+            changed: false,
+        }
+    }
+
+    /// Opens the [LibraryIndex] saved in `file`, which starts empty if the
+    /// file does not exist yet.
+    pub fn open(file: PathBuf) -> Self {
+        let mut index = Self::new();
+        index.load(&file);
+        index.file = Some(file);
+        index
+    }
+
+    /// Opens the [LibraryIndex] of the library at `root`, which is stored on
+    /// the console in `dir`.
+    ///
+    /// A drive's index is named after the drive's UUID, so the same drive finds
+    /// its index again wherever it is mounted. Other directories are named
+    /// after a hash of their path.
+    pub fn for_root(dir: &Path, root: &Path) -> Self {
+        Self::open(dir.join(format!("{}.tsv", volume_key(root))))
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Reads the game at `pck` in the library at `root`.
+    ///
+    /// The pack's header is only read again if the pack changed since it was
+    /// last read, and its icon and manifest are only read again if its
+    /// directory changed. Packs that are not playable games are remembered too.
+    pub fn read(&mut self, root: &Path, pck: &Path) -> Result<Game, GocoError> {
+        let key = pck.strip_prefix(root).unwrap_or(pck).to_path_buf();
+        let stamp = match Stamp::read(pck) {
+            Ok(s) => s,
+            // a pack whose size cannot be read is not remembered
+            Err(_) => return Game::try_from(pck.to_path_buf()),
+        };
+        let dir_modified = pck.parent().map_or(0, get_modified);
+        if let Some(entry) = self.entries.get_mut(&key) {
+            if entry.stamp == stamp {
+                match &entry.header {
+                    Err((Problem::UnsupportedEngine, engine)) => {
+                        return Err(GocoError::UnsupportedEngine(pck.to_path_buf(), engine.clone()))
+                    }
+                    Err((_, reason)) => return Err(GocoError::InvalidPack(pck.to_path_buf(), reason.clone())),
+                    Ok(_) => (),
+                }
+                if entry.dir_modified != dir_modified {
+                    let mut icon = pck.to_path_buf();
+                    icon.set_extension(ICON_EXT);
+                    // a bundle's icon is stored inside the bundle
+                    entry.icon = match icon.is_file() && bundle::is_bundle(pck) == false {
+                        true => Some(icon.strip_prefix(root).unwrap_or(&icon).to_path_buf()),
+                        false => None,
+                    };
+                    entry.identity = Identity::read(pck, entry.identity.get_fingerprint());
+                    entry.dir_modified = dir_modified;
+                    self.changed = true;
+                }
+                let game = Game::indexed(pck.to_path_buf(), entry.icon.as_ref().map(|i| root.join(i)))
+                    .with_identity(entry.identity.clone());
+                return match bundle::is_bundle(pck) {
+                    // the bundle's icon is read from inside the archive again
+                    true => Bundle::open(pck)
+                        .map(|b| game.with_bundle(b))
+                        .map_err(|e| GocoError::from_io(pck.to_path_buf(), e)),
+                    false => Ok(game),
+                };
+            }
+        }
+        let result = Game::read(pck.to_path_buf());
+        let entry = Entry {
+            stamp: stamp,
+            dir_modified: dir_modified,
+            header: match &result {
+                Ok((_, header)) => Ok(header.to_string()),
+                Err(GocoError::InvalidPack(_, reason)) => Err((Problem::CorruptPack, reason.clone())),
+                Err(GocoError::UnsupportedEngine(_, engine)) => Err((Problem::UnsupportedEngine, engine.clone())),
+                // a pack that cannot be read right now is not remembered
+                Err(_) => return result.map(|(game, _)| game),
+            },
+            icon: match &result {
+                Ok((game, _)) => game
+                    .get_icon_path()
+                    .map(|i| i.strip_prefix(root).unwrap_or(&i).to_path_buf()),
+                Err(_) => None,
+            },
+            identity: match &result {
+                Ok((game, _)) => game.get_identity().clone(),
+                Err(_) => Identity::read(pck, None),
+            },
+        };
+        self.entries.insert(key, entry);
+        self.changed = true;
+        result.map(|(game, _)| game)
+    }
+
+    /// Forgets the packs in the library at `root` that are not in `found`.
+    pub fn retain(&mut self, root: &Path, found: &[PathBuf]) {
+        let found: HashSet<&Path> = found
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap_or(p))
+            .collect();
+        let count = self.entries.len();
+        self.entries.retain(|key, _| found.contains(key.as_path()));
+        if self.entries.len() != count {
+            self.changed = true;
+        }
+    }
+
+    /// Writes the index to its file if it changed since it was loaded.
+    pub fn save(&mut self) -> std::io::Result<()> {
+        let file = match (&self.file, self.changed) {
+            (Some(f), true) => f,
+            _ => return Ok(()),
+        };
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut writer = File::create(file)?;
+        for (path, entry) in &self.entries {
+            // a pack that is not a playable game is written with its problem in place of its engine
+            let (engine, reason) = match &entry.header {
+                Ok(engine) => (engine.as_str(), "-"),
+                Err((problem, reason)) => (problem.as_str(), reason.as_str()),
+            };
+            let identity = &entry.identity;
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                entry.stamp.get_size(),
+                entry.stamp.get_modified(),
+                entry.dir_modified,
+                engine,
+                reason.replace('\t', " "),
+                entry.icon.as_ref().map_or(String::from("-"), |i| i.display().to_string()),
+                identity.get_fingerprint().map_or(String::from("-"), |f| format!("{:016x}", f)),
+                identity.get_version().unwrap_or("-").replace('\t', " "),
+                identity.get_project().replace('\t', " "),
+                path.display()
+            )?;
+        }
+        self.changed = false;
+        Ok(())
+    }
+
+    fn load(&mut self, file: &Path) {
+        let file = match File::open(file) {
+            Ok(f) => f,
+            Err(_) => return,
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            let fields: Vec<&str> = line.splitn(10, '\t').collect();
+            if let [size, modified, dir_modified, engine, reason, icon, fingerprint, version, project, path] = fields[..] {
+                if let (Ok(size), Ok(modified), Ok(dir_modified)) = (size.parse(), modified.parse(), dir_modified.parse()) {
+                    self.entries.insert(
+                        PathBuf::from(path),
+                        Entry {
+                            stamp: Stamp::new(size, modified),
+                            dir_modified: dir_modified,
+                            // older indexes wrote `-` in place of the engine of a corrupt pack
+                            header: match (engine, Problem::parse(engine)) {
+                                ("-", _) => Err((Problem::CorruptPack, reason.to_string())),
+                                (_, Some(problem)) => Err((problem, reason.to_string())),
+                                _ => Ok(engine.to_string()),
+                            },
+                            icon: match icon {
+                                "-" => None,
+                                _ => Some(PathBuf::from(icon)),
+                            },
+                            identity: Identity::new(
+                                project,
+                                match version {
+                                    "-" => None,
+                                    _ => Some(version),
+                                },
+                                u64::from_str_radix(fingerprint, 16).ok(),
+                                modified,
+                            ),
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Reads when the file or directory at `path` was last modified (in seconds
+/// since the epoch), which is `0` if it cannot be read.
+fn get_modified(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Names the index of the library at `root` after the UUID of the drive
+/// mounted there, or else after a hash of the path.
+fn volume_key(root: &Path) -> String {
+    #[cfg(target_os = "linux")]
+    if let Some(uuid) = find_uuid(root) {
+        return uuid;
+    }
+    let mut hash = Fnv::new();
+    hash.write(root.to_string_lossy().as_bytes());
+    format!("{:016x}", hash.finish())
+}
+
+/// Finds the UUID of the drive mounted exactly at `root`.
+#[cfg(target_os = "linux")]
+fn find_uuid(root: &Path) -> Option<String> {
+    let root = root.canonicalize().ok()?;
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    let device = mounts.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let (device, point) = (fields.next()?, fields.next()?);
+        // spaces in mount points are escaped as octal
+        match PathBuf::from(point.replace("\\040", " ")) == root {
+            true => PathBuf::from(device).canonicalize().ok(),
+            false => None,
+        }
+    })?;
+    std::fs::read_dir("/dev/disk/by-uuid")
+        .ok()?
+        .filter_map(|e| e.ok())
+        .find(|e| e.path().canonicalize().ok().as_ref() == Some(&device))
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Report;
+    use crate::gamestick::GameStick;
+
+    #[test]
+    fn ut_reuse_unchanged_entries() {
+        let dir = std::env::temp_dir().join(format!("goco-index-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let root = dir.join("stick");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::copy("testenv/GAMESTICK/Pong.pck", root.join("Pong.pck")).unwrap();
+        std::fs::copy("testenv/invalid/corrupt.pck", root.join("corrupt.pck")).unwrap();
+
+        let mut index = LibraryIndex::open(dir.join("index.tsv"));
+        let games = GameStick::load_indexed(&root, &mut index, &mut Report::new()).unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(index.len(), 2);
+        index.save().unwrap();
+
+        // break the pack's header without changing its size or modification time
+        let pck = root.join("Pong.pck");
+        let modified = std::fs::metadata(&pck).unwrap().modified().unwrap();
+        let mut bytes = std::fs::read(&pck).unwrap();
+        bytes[0] = b'X';
+        std::fs::write(&pck, &bytes).unwrap();
+        File::options().append(true).open(&pck).unwrap().set_modified(modified).unwrap();
+
+        // the saved index still remembers the pack as a playable game
+        let mut index = LibraryIndex::open(dir.join("index.tsv"));
+        assert_eq!(index.len(), 2);
+        let game = index.read(&root, &pck).unwrap();
+        assert_eq!(game.get_identity().get_fingerprint().is_some(), true);
+        assert_eq!(index.read(&root, &root.join("corrupt.pck")).is_err(), true);
+
+        // removed packs are forgotten
+        std::fs::remove_file(root.join("corrupt.pck")).unwrap();
+        GameStick::load_indexed(&root, &mut index, &mut Report::new()).unwrap();
+        assert_eq!(index.len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use iced::keyboard::KeyCode;
+
+use crate::config::Config;
+
+/// An abstract console input that can be triggered by one or more keys.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Action {
+    Left,
+    Right,
+    Up,
+    Down,
+    Confirm,
+    Back,
+    Eject,
+    Menu,
+    Home,
+    Refresh,
+}
+
+impl Action {
+    /// Every available action.
+    pub const ALL: [Action; 10] = [
+        Action::Left,
+        Action::Right,
+        Action::Up,
+        Action::Down,
+        Action::Confirm,
+        Action::Back,
+        Action::Eject,
+        Action::Menu,
+        Action::Home,
+        Action::Refresh,
+    ];
+
+    /// The action's key in the configuration file's `[bindings]` table.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Confirm => "confirm",
+            Self::Back => "back",
+            Self::Eject => "eject",
+            Self::Menu => "menu",
+            Self::Home => "home",
+            Self::Refresh => "refresh",
+        }
+    }
+
+    /// The keys bound to the action when the configuration file does not
+    /// override them.
+    fn default_keys(&self) -> Vec<KeyCode> {
+        match self {
+            Self::Left => vec![KeyCode::A, KeyCode::Left],
+            Self::Right => vec![KeyCode::D, KeyCode::Right],
+            Self::Up => vec![KeyCode::W, KeyCode::Up],
+            Self::Down => vec![KeyCode::S, KeyCode::Down],
+            Self::Confirm => vec![KeyCode::Space, KeyCode::Enter, KeyCode::NumpadEnter],
+            Self::Back => vec![KeyCode::Escape, KeyCode::Backspace],
+            // the RaspberryPi has a dedicated eject button
+            #[cfg(not(feature = "rpi"))]
+            Self::Eject => vec![KeyCode::E],
+            #[cfg(feature = "rpi")]
+            Self::Eject => vec![],
+            Self::Menu => vec![KeyCode::M],
+            Self::Home => vec![KeyCode::H, KeyCode::Home],
+            Self::Refresh => vec![KeyCode::R, KeyCode::F5],
+        }
+    }
+}
+
+/// A table mapping each [Action] to the keys that trigger it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Bindings {
+    table: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            table: Action::ALL.iter().map(|a| (*a, a.default_keys())).collect(),
+        }
+    }
+}
+
+impl Bindings {
+    /// Creates the bindings from the configuration file's `[bindings]` table.
+    ///
+    /// Each entry replaces the default keys of its action with a comma-separated
+    /// list of key names, such as `left = A, Left`. Unknown key names are skipped.
+    pub fn from_config(config: &Config) -> Self {
+        let mut bindings = Self::default();
+        for action in Action::ALL {
+            if config.get("bindings", action.as_str()).is_none() == true {
+                continue;
+            }
+            let keys = config
+                .get_list("bindings", action.as_str())
+                .into_iter()
+                .filter_map(|name| match Self::parse_key(name) {
+                    Some(k) => Some(k),
+                    None => {
+                        log::error!("Unknown key {:?} bound to action {:?}", name, action.as_str());
+                        None
+                    }
+                })
+                .collect();
+            bindings.table.insert(action, keys);
+        }
+        bindings
+    }
+
+    /// Accesses every action triggered by the key `key`.
+    pub fn get_actions(&self, key: KeyCode) -> Vec<Action> {
+        Action::ALL
+            .into_iter()
+            .filter(|a| self.table.get(a).map_or(false, |keys| keys.contains(&key)))
+            .collect()
+    }
+
+    /// Converts the name of a key as written in the configuration file to its
+    /// [KeyCode]. Names are case-insensitive.
+    pub fn parse_key(name: &str) -> Option<KeyCode> {
+        let name = name.to_lowercase();
+        // letters and digits
+        if name.len() == 1 {
+            let c = name.chars().next().unwrap();
+            let letters = [
+                KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+                KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+                KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+                KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+            ];
+            let digits = [
+                KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+                KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+            ];
+            return match c {
+                'a'..='z' => Some(letters[c as usize - 'a' as usize]),
+                '0'..='9' => Some(digits[c as usize - '0' as usize]),
+                _ => None,
+            };
+        }
+        match name.as_str() {
+            "left" => Some(KeyCode::Left),
+            "right" => Some(KeyCode::Right),
+            "up" => Some(KeyCode::Up),
+            "down" => Some(KeyCode::Down),
+            "enter" | "return" => Some(KeyCode::Enter),
+            "space" => Some(KeyCode::Space),
+            "escape" | "esc" => Some(KeyCode::Escape),
+            "backspace" => Some(KeyCode::Backspace),
+            "tab" => Some(KeyCode::Tab),
+            "home" => Some(KeyCode::Home),
+            "end" => Some(KeyCode::End),
+            "insert" => Some(KeyCode::Insert),
+            "delete" => Some(KeyCode::Delete),
+            "pageup" => Some(KeyCode::PageUp),
+            "pagedown" => Some(KeyCode::PageDown),
+            "f1" => Some(KeyCode::F1),
+            "f2" => Some(KeyCode::F2),
+            "f3" => Some(KeyCode::F3),
+            "f4" => Some(KeyCode::F4),
+            "f5" => Some(KeyCode::F5),
+            "f6" => Some(KeyCode::F6),
+            "f7" => Some(KeyCode::F7),
+            "f8" => Some(KeyCode::F8),
+            "f9" => Some(KeyCode::F9),
+            "f10" => Some(KeyCode::F10),
+            "f11" => Some(KeyCode::F11),
+            "f12" => Some(KeyCode::F12),
+            "numpad0" => Some(KeyCode::Numpad0),
+            "numpad1" => Some(KeyCode::Numpad1),
+            "numpad2" => Some(KeyCode::Numpad2),
+            "numpad3" => Some(KeyCode::Numpad3),
+            "numpad4" => Some(KeyCode::Numpad4),
+            "numpad5" => Some(KeyCode::Numpad5),
+            "numpad6" => Some(KeyCode::Numpad6),
+            "numpad7" => Some(KeyCode::Numpad7),
+            "numpad8" => Some(KeyCode::Numpad8),
+            "numpad9" => Some(KeyCode::Numpad9),
+            "numpadenter" => Some(KeyCode::NumpadEnter),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_default_bindings() {
+        let bindings = Bindings::default();
+        assert_eq!(bindings.get_actions(KeyCode::Left), vec![Action::Left]);
+        assert_eq!(bindings.get_actions(KeyCode::D), vec![Action::Right]);
+        assert_eq!(bindings.get_actions(KeyCode::Enter), vec![Action::Confirm]);
+        assert_eq!(bindings.get_actions(KeyCode::Escape), vec![Action::Back]);
+        assert_eq!(bindings.get_actions(KeyCode::Q), Vec::<Action>::new());
+    }
+
+    #[test]
+    fn ut_config_bindings() {
+        let config = Config::parse("[bindings]\nleft = J, left\nconfirm = K, bogus\nmenu = Enter\n").unwrap();
+        let bindings = Bindings::from_config(&config);
+        // overridden actions lose their default keys
+        assert_eq!(bindings.get_actions(KeyCode::A), Vec::<Action>::new());
+        assert_eq!(bindings.get_actions(KeyCode::J), vec![Action::Left]);
+        assert_eq!(bindings.get_actions(KeyCode::Left), vec![Action::Left]);
+        assert_eq!(bindings.get_actions(KeyCode::K), vec![Action::Confirm]);
+        assert_eq!(bindings.get_actions(KeyCode::Enter), vec![Action::Menu]);
+        // untouched actions keep their default keys
+        assert_eq!(bindings.get_actions(KeyCode::W), vec![Action::Up]);
+    }
+
+    #[test]
+    fn ut_parse_key() {
+        assert_eq!(Bindings::parse_key("a"), Some(KeyCode::A));
+        assert_eq!(Bindings::parse_key("Z"), Some(KeyCode::Z));
+        assert_eq!(Bindings::parse_key("7"), Some(KeyCode::Key7));
+        assert_eq!(Bindings::parse_key("Return"), Some(KeyCode::Enter));
+        assert_eq!(Bindings::parse_key("shift"), None);
+    }
+}
\ No newline at end of file
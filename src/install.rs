@@ -0,0 +1,198 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::GocoError;
+use crate::game::Game;
+use crate::gamestick::GameStick;
+use crate::diagnostics::Report;
+use crate::index::LibraryIndex;
+use crate::library::{LibrarySource, SourceKind};
+
+/// The directory under `GOCO_ROOT` where installed games are stored.
+pub const LIBRARY_DIR: &str = "library";
+
+/// The games installed to the console's internal storage, which can be played
+/// without the [GameStick].
+#[derive(Debug, PartialEq, Clone)]
+pub struct InternalLibrary {
+    dir: PathBuf,
+}
+
+impl InternalLibrary {
+    /// Creates an [InternalLibrary] stored in the directory `dir`.
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Creates the [InternalLibrary] stored under `GOCO_ROOT/library`.
+    pub fn open() -> Self {
+        Self::new(&crate::env::root().join(LIBRARY_DIR))
+    }
+
+    pub fn get_dir(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    /// Checks if the `game` is stored in the internal library.
+    pub fn contains(&self, game: &Game) -> bool {
+        game.get_pck().starts_with(&self.dir)
+    }
+
+    /// Checks if a game with the same file name as `game` is installed.
+    pub fn is_installed(&self, game: &Game) -> bool {
+        self.contains(game) == true
+            || game.get_pck().file_name().map_or(false, |name| self.dir.join(name).is_file())
+    }
+
+    /// Copies the `game`'s pack, icon and manifest into the internal library.
+    ///
+    /// Each file is copied under a temporary name first, so a failed install
+    /// never leaves a partial game in the library.
+    pub fn install(&self, game: &Game) -> Result<(), GocoError> {
+        let fail = |reason: String| GocoError::InstallFailed(game.get_pck().clone(), reason);
+        if self.is_installed(game) == true {
+            return Err(fail(String::from("a game with the same name is already installed")));
+        }
+        std::fs::create_dir_all(&self.dir).map_err(|e| fail(e.to_string()))?;
+        let mut copied: Vec<PathBuf> = Vec::new();
+        for file in game.get_files() {
+            let dest = self.dir.join(file.file_name().unwrap_or_default());
+            let mut partial = dest.clone().into_os_string();
+            partial.push(".part");
+            let result = std::fs::copy(&file, &partial)
+                // keep the modification time, which tells the newest copy of a game apart
+                .and_then(|_| {
+                    let modified = std::fs::metadata(&file)?.modified()?;
+                    std::fs::File::options().write(true).open(&partial)?.set_modified(modified)
+                })
+                .and_then(|_| std::fs::rename(&partial, &dest));
+            if let Err(e) = result {
+                // undo the files copied so far
+                let _ = std::fs::remove_file(&partial);
+                for path in copied {
+                    let _ = std::fs::remove_file(path);
+                }
+                return Err(fail(e.to_string()));
+            }
+            copied.push(dest);
+        }
+        Ok(())
+    }
+
+    /// Removes the installed `game`'s pack, icon and manifest from the internal library.
+    pub fn uninstall(&self, game: &Game) -> Result<(), GocoError> {
+        if self.contains(game) == false {
+            return Err(GocoError::InstallFailed(
+                game.get_pck().clone(),
+                String::from("the game is not installed"),
+            ));
+        }
+        for file in game.get_files() {
+            std::fs::remove_file(&file).map_err(|e| GocoError::from_io(file.clone(), e))?;
+        }
+        Ok(())
+    }
+
+    /// Adds up the size of every file in the internal library (in bytes).
+    pub fn get_usage(&self) -> u64 {
+        match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok()?.metadata().ok())
+                .filter(|m| m.is_file())
+                .map(|m| m.len())
+                .sum(),
+            Err(_) => 0,
+        }
+    }
+}
+
+impl LibrarySource for InternalLibrary {
+    fn get_id(&self) -> String {
+        String::from("internal")
+    }
+
+    fn get_kind(&self) -> SourceKind {
+        SourceKind::Internal
+    }
+
+    fn get_root(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    /// The internal storage is always available, even before a game is installed.
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn read_games(&self, index: &mut LibraryIndex, report: &mut Report) -> Result<Vec<Game>, GocoError> {
+        match self.dir.is_dir() {
+            true => GameStick::load_indexed(&self.dir, index, report),
+            false => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Adds up the size of the `game`'s pack, icon and manifest (in bytes).
+pub fn get_game_size(game: &Game) -> u64 {
+    game.get_files()
+        .iter()
+        .filter_map(|f| std::fs::metadata(f).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Writes a number of `bytes` in the largest unit that keeps it above 1, such as `1.5 MB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads the games installed to the `library` as the console does.
+    fn read(library: &InternalLibrary) -> Vec<Game> {
+        library.read_games(&mut LibraryIndex::new(), &mut Report::new()).unwrap()
+    }
+
+    #[test]
+    fn ut_install_and_uninstall() {
+        let dir = std::env::temp_dir().join(format!("goco-library-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let library = InternalLibrary::new(&dir);
+        assert_eq!(read(&library).len(), 0);
+
+        let game = Game::try_from(PathBuf::from("testenv/GAMESTICK/Pong.pck")).unwrap();
+        library.install(&game).unwrap();
+        assert_eq!(library.is_installed(&game), true);
+        assert_eq!(library.install(&game).is_err(), true);
+        let installed = read(&library);
+        assert_eq!(installed.len(), 1);
+        assert_eq!(library.contains(&installed[0]), true);
+        assert_eq!(installed[0].get_icon_path().is_some(), true);
+        assert_eq!(library.get_usage(), get_game_size(&game));
+
+        library.uninstall(&installed[0]).unwrap();
+        assert_eq!(read(&library).len(), 0);
+        assert_eq!(library.uninstall(&game).is_err(), true);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn ut_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+}
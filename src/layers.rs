@@ -0,0 +1,188 @@
+use iced_native::event::{self, Event};
+use iced_native::layout::{self, Layout};
+use iced_native::mouse;
+use iced_native::overlay;
+use iced_native::renderer;
+use iced_native::widget::{Tree, Widget};
+use iced_native::{Clipboard, Element, Length, Point, Rectangle, Shell, Size};
+
+/// A widget that draws its children on top of one another.
+///
+/// Every child is laid out to fill the same space, and later children are
+/// drawn above earlier children. This allows for background images and
+/// overlays that `iced` does not provide on its own.
+///
+/// Pointer events only reach the topmost modal layer and the layers above it,
+/// so the screens underneath a modal layer cannot be clicked or tapped.
+pub struct Layers<'a, Message, Renderer> {
+    children: Vec<Element<'a, Message, Renderer>>,
+    /// The index of the lowest layer that receives pointer events.
+    modal: usize,
+}
+
+impl<'a, Message, Renderer> Layers<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    /// Creates a new [Layers] widget with `base` as the bottom layer.
+    pub fn new(base: impl Into<Element<'a, Message, Renderer>>) -> Self {
+        Self {
+            children: vec![base.into()],
+            modal: 0,
+        }
+    }
+
+    /// Adds a layer on top of the existing layers.
+    pub fn push(mut self, layer: impl Into<Element<'a, Message, Renderer>>) -> Self {
+        self.children.push(layer.into());
+        self
+    }
+
+    /// Adds a layer on top of the existing layers that keeps pointer events
+    /// from reaching the layers underneath it.
+    pub fn push_modal(mut self, layer: impl Into<Element<'a, Message, Renderer>>) -> Self {
+        self.modal = self.children.len();
+        self.children.push(layer.into());
+        self
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for Layers<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        Length::Fill
+    }
+
+    fn height(&self) -> Length {
+        Length::Fill
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        self.children.iter().map(Tree::new).collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(&self.children);
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        let size = limits.width(Length::Fill).height(Length::Fill).max();
+        let children = self
+            .children
+            .iter()
+            .map(|child| {
+                child
+                    .as_widget()
+                    .layout(renderer, &layout::Limits::new(Size::ZERO, size))
+            })
+            .collect();
+        layout::Node::with_children(size, children)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        // other events, such as key presses, reach every layer
+        let lowest = match &event {
+            Event::Mouse(_) | Event::Touch(_) => self.modal,
+            _ => 0,
+        };
+        self.children
+            .iter_mut()
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .skip(lowest)
+            .map(|((child, state), layout)| {
+                child.as_widget_mut().on_event(
+                    state,
+                    event.clone(),
+                    layout,
+                    cursor_position,
+                    renderer,
+                    clipboard,
+                    shell,
+                )
+            })
+            .fold(event::Status::Ignored, event::Status::merge)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        for (i, ((child, state), layout)) in self
+            .children
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .enumerate()
+        {
+            let draw = |renderer: &mut Renderer| {
+                child
+                    .as_widget()
+                    .draw(state, renderer, theme, style, layout, cursor_position, viewport)
+            };
+            // each layer above the base is drawn in its own renderer layer to stay on top
+            match i {
+                0 => draw(renderer),
+                _ => renderer.with_layer(layout.bounds(), draw),
+            }
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.children
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .skip(self.modal)
+            .map(|((child, state), layout)| {
+                child
+                    .as_widget()
+                    .mouse_interaction(state, layout, cursor_position, viewport, renderer)
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        overlay::from_children(&mut self.children, tree, layout, renderer)
+    }
+}
+
+impl<'a, Message, Renderer> From<Layers<'a, Message, Renderer>> for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: iced_native::Renderer + 'a,
+{
+    fn from(layers: Layers<'a, Message, Renderer>) -> Self {
+        Element::new(layers)
+    }
+}
\ No newline at end of file
@@ -4,24 +4,206 @@ mod game;
 mod gamestick;
 mod os;
 mod animator;
+mod cli;
+mod config;
+mod pack;
+mod input;
+mod gamepad;
+mod pointer;
+mod metrics;
+mod theme;
+mod settings;
+mod layers;
+mod tap;
+mod locale;
+mod tween;
+mod notify;
+mod error;
+mod logger;
+mod focus;
+mod cache;
+mod install;
+mod details;
+mod library;
+mod index;
+mod identity;
+mod watch;
+mod diagnostics;
+mod bundle;
 
 #[cfg(feature = "rpi")]
 mod gpio;
 
+use std::path::PathBuf;
+
 use os::Os;
 use iced::Application;
 use iced::Settings;
 
+use cache::{Cache, Progress};
+use cli::{Cli, Subcommand};
+use config::Config;
+use diagnostics::Report;
+use engine::Engine;
+use game::Game;
+use gamestick::GameStick;
+use index::LibraryIndex;
+use logger::Logger;
+use pack::PackKind;
+
 pub fn go() -> u8 {
-    println!("info: Booting up GOCO ...");
+    let cli = match Cli::parse(std::env::args().skip(1)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            return 101;
+        }
+    };
+    if cli.help == true {
+        println!("{}", cli::USAGE);
+        return 0;
+    }
+    if cli.version == true {
+        println!("goco {}", env!("CARGO_PKG_VERSION"));
+        return 0;
+    }
+    // command-line options take priority over the environment and configuration file
+    cli.export();
+    let loaded = Config::load(&Config::default_path());
+    // only the console keeps a log file; subcommands log to the terminal
+    Logger::init(loaded.as_ref().unwrap_or(&Config::new()), cli.command == Subcommand::Boot);
+    let config = match loaded {
+        Ok(config) => config,
+        Err(e) => {
+            log::error!("{}", e);
+            Config::unreadable()
+        }
+    };
+    config.export_defaults();
+
+    match cli.command {
+        Subcommand::Boot => boot(config),
+        Subcommand::Scan(dir) => scan(&dir),
+        Subcommand::Play(pck) => play(pck, &config),
+        Subcommand::Info(pck) => info(pck),
+    }
+}
+
+/// Runs the console's graphical interface.
+fn boot(config: Config) -> u8 {
+    log::info!("Booting up GOCO ...");
     match Os::run(Settings {
+            flags: config,
+            window: iced::window::Settings {
+                size: os::WINDOW_SIZE,
+                ..iced::window::Settings::default()
+            },
             exit_on_close_request: false,
             ..Settings::default()
         }) {
             Ok(_) => 0,
             Err(e) => {
-                eprintln!("error: {}", e);
+                log::error!("{}", e);
                 101
             }
     }
+}
+
+/// Prints every game the library loader finds under the directory `dir`,
+/// followed by the problems found with the files it skipped.
+fn scan(dir: &PathBuf) -> u8 {
+    if dir.is_dir() == false {
+        log::error!("Directory {:?} does not exist", dir);
+        return 101;
+    }
+    let mut report = Report::new();
+    let library = match GameStick::load_indexed(dir, &mut LibraryIndex::new(), &mut report) {
+        Ok(l) => l,
+        Err(e) => {
+            log::error!("{}", e);
+            return 101;
+        }
+    };
+    for game in &library {
+        println!("{}\t{}", game.get_name(), game.get_pck().display());
+    }
+    if report.is_empty() == false {
+        println!("\nLibrary diagnostics:");
+        for finding in report.get_findings() {
+            println!(
+                "{}\t{}\t{}",
+                finding.get_problem().as_str(),
+                finding.get_path().display(),
+                finding.get_detail()
+            );
+        }
+    }
+    log::info!("Found {} game(s) in {:?}", library.len(), dir);
+    if report.count_skipped() > 0 {
+        log::warn!("Skipped {} file(s) in {:?}", report.count_skipped(), dir);
+    }
+    0
+}
+
+/// Launches the game `pck` on the engine, or on its own if it is a
+/// self-contained executable, and waits for it to exit.
+///
+/// A bundle's pack is first extracted into the cache configured by `config`.
+fn play(pck: PathBuf, config: &Config) -> u8 {
+    let game = match Game::try_from(pck) {
+        Ok(g) => g,
+        Err(e) => {
+            log::error!("{}", e);
+            return 101;
+        }
+    };
+    let mut engine = Engine::new();
+    let started = match game.get_bundle() {
+        Some(bundle) => {
+            bundle.prepare_save();
+            let mut cache = Cache::from_config(config).unwrap_or_else(|| Cache::for_bundles(config));
+            let pack = match cache.lookup(game.get_pck()) {
+                Some(pack) => Ok(pack),
+                None => cache.store(game.get_pck(), &Progress::new()),
+            };
+            pack.and_then(|pack| engine.play_pack(&pack, PackKind::Pck))
+        }
+        None => engine.play_game(&game),
+    };
+    match started.and_then(|_| engine.wait_game()) {
+        Ok(true) => 0,
+        Ok(false) => 101,
+        Err(e) => {
+            log::error!("{}", e);
+            101
+        }
+    }
+}
+
+/// Prints the metadata stored for the game `pck`.
+fn info(pck: PathBuf) -> u8 {
+    let (game, header) = match Game::read(pck) {
+        Ok(read) => read,
+        Err(e) => {
+            log::error!("{}", e);
+            return 101;
+        }
+    };
+    println!("name: {}", game.get_name());
+    println!("pack: {}", game.get_pck().display());
+    if let Ok(meta) = std::fs::metadata(game.get_pck()) {
+        println!("size: {} bytes", meta.len());
+    }
+    match (game.get_icon_path(), game.has_icon()) {
+        (Some(p), _) => println!("icon: {}", p.display()),
+        (None, true) => println!("icon: inside the bundle"),
+        (None, false) => println!("icon: none"),
+    }
+    if let Some(bundle) = game.get_bundle() {
+        println!("banner: {}", bundle.has_banner());
+        println!("screenshots: {}", bundle.get_screenshot_count());
+        println!("save template: {}", bundle.has_save_template());
+    }
+    println!("engine: {}", header);
+    0
 }
\ No newline at end of file
@@ -0,0 +1,539 @@
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::diagnostics::Report;
+use crate::error::GocoError;
+use crate::game::Game;
+use crate::gamestick::GameStick;
+use crate::cache::CACHE_DIR;
+use crate::index::{LibraryIndex, INDEX_DIR};
+use crate::install::InternalLibrary;
+
+/// Where a [LibrarySource] stores its games, which is shown on each game's badge.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SourceKind {
+    /// The console's internal storage.
+    Internal,
+    /// A USB drive such as the GAMESTICK.
+    Stick,
+    /// A directory configured by the owner, such as an NFS mount.
+    Directory,
+}
+
+/// A place the console reads games from.
+pub trait LibrarySource: Debug + Send + Sync {
+    /// A name unique among the console's sources, which is remembered by each
+    /// of the source's games.
+    fn get_id(&self) -> String;
+
+    fn get_kind(&self) -> SourceKind;
+
+    /// References the directory the source's games are read from.
+    fn get_root(&self) -> &PathBuf;
+
+    /// Checks if the source can currently be read from.
+    fn is_available(&self) -> bool {
+        self.get_root().is_dir()
+    }
+
+    /// Checks if the source can be ejected from the console.
+    fn is_removable(&self) -> bool {
+        false
+    }
+
+    /// Reads the source's games, only reading the files that changed since
+    /// they were remembered in the `index`, and records the files that were
+    /// skipped in the `report`.
+    fn read_games(&self, index: &mut LibraryIndex, report: &mut Report) -> Result<Vec<Game>, GocoError> {
+        GameStick::load_indexed(self.get_root(), index, report)
+    }
+
+    /// Attempts to safely remove the source from the console.
+    fn eject(&self) -> Result<(), GocoError> {
+        Err(GocoError::MountError(self.get_root().clone(), String::from("the source cannot be ejected")))
+    }
+}
+
+/// A directory of games configured by the owner, such as an NFS mount.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Directory {
+    root: PathBuf,
+}
+
+impl Directory {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root: root }
+    }
+}
+
+impl LibrarySource for Directory {
+    fn get_id(&self) -> String {
+        format!("dir:{}", self.root.display())
+    }
+
+    fn get_kind(&self) -> SourceKind {
+        SourceKind::Directory
+    }
+
+    fn get_root(&self) -> &PathBuf {
+        &self.root
+    }
+}
+
+/// A change to the [Library] found while refreshing its sources.
+#[derive(Debug)]
+pub enum Change {
+    /// The source with the id was read with the number of games.
+    Added(String, usize),
+    /// The source with the id is no longer available and its games were removed.
+    Removed(String),
+    /// A source is available but could not be read.
+    Failed(GocoError),
+}
+
+/// Every source the console reads games from, along with the games read from
+/// the sources that are available.
+#[derive(Debug, Clone)]
+pub struct Library {
+    sources: Vec<Arc<dyn LibrarySource>>,
+    /// The games read from each source, in the same order as the sources.
+    /// Sources that are not loaded have no entry.
+    loaded: Vec<Option<Vec<Game>>>,
+    /// The problems found while reading each source, in the same order as the sources.
+    reports: Vec<Report>,
+    /// Whether each source failed to be read, in the same order as the sources.
+    /// A failed source is not read again until it reappears or is retried.
+    failed: Vec<bool>,
+    /// The directory storing the index of each source, if the sources are indexed.
+    index_dir: Option<PathBuf>,
+}
+
+impl Library {
+    /// Creates a [Library] without any sources.
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            loaded: Vec::new(),
+            reports: Vec::new(),
+            failed: Vec::new(),
+            index_dir: None,
+        }
+    }
+
+    /// Remembers the games read from each source in an index stored in `dir`,
+    /// so reading a source again only reads the files that changed.
+    pub fn with_index(mut self, dir: PathBuf) -> Self {
+        self.index_dir = Some(dir);
+        self
+    }
+
+    /// Creates the console's [Library] from the `[library]` table of the configuration.
+    ///
+    /// Sources earlier in the list win when the same game is found in more than
+    /// one source: the internal storage comes first, followed by the configured
+    /// `dirs`, the GAMESTICK, and then any other USB drives named in `sticks`.
+    pub fn from_config(config: &Config) -> Self {
+        let mut library = Self::new().with_index(crate::env::root().join(CACHE_DIR).join(INDEX_DIR));
+        library.push(InternalLibrary::open());
+        for dir in config.get_list("library", "dirs") {
+            library.push(Directory::new(PathBuf::from(dir)));
+        }
+        library.push(GameStick::new());
+        for label in config.get_list("library", "sticks") {
+            library.push(GameStick::named(label));
+        }
+        library
+    }
+
+    /// Adds the `source` to the end of the library's sources.
+    pub fn push(&mut self, source: impl LibrarySource + 'static) {
+        self.sources.push(Arc::new(source));
+        self.loaded.push(None);
+        self.reports.push(Report::new());
+        self.failed.push(false);
+    }
+
+    pub fn count_sources(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Accesses the source with the id `id`.
+    pub fn get_source(&self, id: &str) -> Option<&dyn LibrarySource> {
+        self.find(id).map(|i| self.sources[i].as_ref())
+    }
+
+    /// Checks if the source with the id `id` has its games in the library.
+    pub fn is_loaded(&self, id: &str) -> bool {
+        self.find(id).map_or(false, |i| self.loaded[i].is_some())
+    }
+
+    /// Lists the sources whose games are in the library.
+    pub fn get_loaded_sources(&self) -> Vec<&dyn LibrarySource> {
+        self.sources
+            .iter()
+            .zip(&self.loaded)
+            .filter(|(_, l)| l.is_some())
+            .map(|(s, _)| s.as_ref())
+            .collect()
+    }
+
+    /// Checks if the source the `game` was read from can currently be read from.
+    pub fn is_available(&self, game: &Game) -> bool {
+        self.get_source(game.get_source()).map_or(false, |s| s.is_available())
+    }
+
+    /// Checks if any removable source, such as the GAMESTICK, is available.
+    #[cfg(feature = "rpi")]
+    pub fn has_removable(&self) -> bool {
+        self.sources.iter().any(|s| s.is_removable() == true && s.is_available() == true)
+    }
+
+    /// Checks if any source has become available since it was last loaded.
+    pub fn has_arrivals(&self) -> bool {
+        self.get_arrivals().is_empty() == false
+    }
+
+    /// Lists the ids of the sources that became available since they were
+    /// last loaded, skipping the sources that failed to be read.
+    pub fn get_arrivals(&self) -> Vec<String> {
+        (0..self.sources.len())
+            .filter(|&i| self.loaded[i].is_none() && self.failed[i] == false && self.sources[i].is_available() == true)
+            .map(|i| self.sources[i].get_id())
+            .collect()
+    }
+
+    /// Finds the id of the loaded removable source to eject, preferring the
+    /// source with the id `preferred`.
+    pub fn find_ejectable(&self, preferred: Option<&str>) -> Option<String> {
+        let ejectable: Vec<String> = self
+            .sources
+            .iter()
+            .zip(&self.loaded)
+            .filter(|(s, l)| l.is_some() && s.is_removable() == true)
+            .map(|(s, _)| s.get_id())
+            .collect();
+        match preferred {
+            Some(id) if ejectable.iter().any(|e| e == id) => Some(id.to_string()),
+            _ => ejectable.into_iter().next(),
+        }
+    }
+
+    /// Reads the games of the source with the id `id` into the library.
+    ///
+    /// Returns the number of games read. Errors if the source cannot be read,
+    /// in which case its games are left unchanged.
+    pub fn load(&mut self, id: &str) -> Result<usize, GocoError> {
+        let i = match self.find(id) {
+            Some(i) => i,
+            None => return Ok(0),
+        };
+        let (games, report) = self.read(id)?;
+        let count = games.len();
+        self.loaded[i] = Some(games);
+        self.reports[i] = report;
+        Ok(count)
+    }
+
+    /// Reads the games of the source with the id `id` without adding them to
+    /// the library, so a copy of the library can read its sources on another
+    /// thread. The files that were skipped are listed in the returned [Report].
+    pub fn read(&self, id: &str) -> Result<(Vec<Game>, Report), GocoError> {
+        let source = match self.find(id) {
+            Some(i) => &self.sources[i],
+            None => return Ok((Vec::new(), Report::new())),
+        };
+        let mut index = match &self.index_dir {
+            Some(dir) => LibraryIndex::for_root(dir, source.get_root()),
+            None => LibraryIndex::new(),
+        };
+        let mut report = Report::new();
+        let games = source.read_games(&mut index, &mut report)?;
+        if let Err(e) = index.save() {
+            log::warn!("Failed to save the library index of {:?}: {}", source.get_root(), e);
+        }
+        let games = games
+            .into_iter()
+            .map(|g| g.with_source(id))
+            .collect();
+        Ok((games, report))
+    }
+
+    /// Replaces the games of the loaded source with the id `id` with the
+    /// `games` read from it by [Library::read], along with its `report`. A
+    /// source that was removed while it was being read stays removed.
+    ///
+    /// Returns `true` if the source's games changed.
+    pub fn update(&mut self, id: &str, games: Vec<Game>, report: Report) -> bool {
+        let i = match self.find(id) {
+            Some(i) if self.loaded[i].is_some() => i,
+            _ => return false,
+        };
+        self.reports[i] = report;
+        match self.loaded[i].as_ref() == Some(&games) {
+            true => false,
+            false => {
+                self.loaded[i] = Some(games);
+                true
+            }
+        }
+    }
+
+    /// Adds the `games` read from the source with the id `id` by [Library::read]
+    /// to the library, along with its `report`. A source that was removed
+    /// while it was being read stays removed.
+    ///
+    /// Returns `true` if the source's games were added.
+    pub fn add(&mut self, id: &str, games: Vec<Game>, report: Report) -> bool {
+        let i = match self.find(id) {
+            Some(i) if self.loaded[i].is_none() && self.sources[i].is_available() == true => i,
+            _ => return false,
+        };
+        self.loaded[i] = Some(games);
+        self.reports[i] = report;
+        self.failed[i] = false;
+        true
+    }
+
+    /// Remembers that the source with the id `id` could not be read, so it is
+    /// not read again until it reappears or [Library::retry_failed] is called.
+    pub fn mark_failed(&mut self, id: &str) {
+        if let Some(i) = self.find(id) {
+            self.failed[i] = true;
+        }
+    }
+
+    /// Reads the sources that failed to be read again on the next refresh.
+    pub fn retry_failed(&mut self) {
+        self.failed.iter_mut().for_each(|f| *f = false);
+    }
+
+    /// Removes the games of the source with the id `id` from the library.
+    ///
+    /// Returns `true` if the source's games were in the library.
+    pub fn unload(&mut self, id: &str) -> bool {
+        match self.find(id) {
+            Some(i) => {
+                self.reports[i] = Report::new();
+                self.loaded[i].take().is_some()
+            }
+            None => false,
+        }
+    }
+
+    /// Reads the games of every source that became available and removes the
+    /// games of every source that is no longer available.
+    pub fn refresh(&mut self) -> Vec<Change> {
+        let mut changes = self.remove_departed();
+        for id in self.get_arrivals() {
+            match self.load(&id) {
+                Ok(count) => changes.push(Change::Added(id, count)),
+                Err(e) => {
+                    self.mark_failed(&id);
+                    changes.push(Change::Failed(e));
+                }
+            }
+        }
+        changes
+    }
+
+    /// Removes the games of every source that is no longer available, without
+    /// reading the sources that became available.
+    pub fn remove_departed(&mut self) -> Vec<Change> {
+        let mut changes = Vec::new();
+        for i in 0..self.sources.len() {
+            if self.sources[i].is_available() == true {
+                continue;
+            }
+            // a source that failed is read again once it reappears
+            self.failed[i] = false;
+            if self.loaded[i].is_some() {
+                let id = self.sources[i].get_id();
+                self.unload(&id);
+                changes.push(Change::Removed(id));
+            }
+        }
+        changes
+    }
+
+    /// Collects the problems found while reading every loaded source, in the
+    /// order of the sources.
+    pub fn get_report(&self) -> Report {
+        let mut report = Report::new();
+        for r in &self.reports {
+            report.append(r);
+        }
+        report
+    }
+
+    /// Merges the games of every loaded source into one list in the order of
+    /// the sources. Only the newest of a game's copies is listed, and the
+    /// earliest source wins between identical copies.
+    pub fn get_games(&self) -> Vec<Game> {
+        self.group_copies()
+            .into_iter()
+            .map(|copies| copies.into_iter().next().unwrap())
+            .collect()
+    }
+
+    /// Lists every copy of the `game` found in the loaded sources, starting
+    /// with the copy listed by [Library::get_games].
+    pub fn get_copies(&self, game: &Game) -> Vec<Game> {
+        self.group_copies()
+            .into_iter()
+            .find(|copies| copies.iter().any(|g| g.get_pck() == game.get_pck()))
+            .unwrap_or_default()
+    }
+
+    /// Groups the games of every loaded source into copies of the same game,
+    /// in the order each game was first found. The newest copy of each game
+    /// is moved to the front of its group.
+    fn group_copies(&self) -> Vec<Vec<Game>> {
+        let mut groups: Vec<Vec<Game>> = Vec::new();
+        for game in self.loaded.iter().flatten().flatten() {
+            let identity = game.get_identity();
+            let found = groups
+                .iter()
+                .position(|copies| copies.iter().any(|c| identity.is_copy_of(c.get_identity()) == true));
+            match found {
+                Some(i) => {
+                    // a newer copy replaces the copy listed so far
+                    match identity.is_newer_than(groups[i][0].get_identity()) {
+                        true => groups[i].insert(0, game.clone()),
+                        false => groups[i].push(game.clone()),
+                    }
+                }
+                None => groups.push(vec![game.clone()]),
+            }
+        }
+        groups
+    }
+
+    fn find(&self, id: &str) -> Option<usize> {
+        self.sources.iter().position(|s| s.get_id() == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_merge_sources() {
+        let mut library = Library::new();
+        library.push(Directory::new(PathBuf::from("testenv/GAMESTICK")));
+        library.push(GameStick::test_new());
+        let changes = library.refresh();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(library.is_loaded("stick:GAMESTICK"), true);
+        // the same games on both sources are only listed once
+        let games = library.get_games();
+        assert_eq!(games.len(), 4);
+        assert_eq!(games.iter().all(|g| g.get_source() == "dir:testenv/GAMESTICK"), true);
+        // removing a source only removes its own games
+        assert_eq!(library.unload("dir:testenv/GAMESTICK"), true);
+        let games = library.get_games();
+        assert_eq!(games.len(), 4);
+        assert_eq!(games.iter().all(|g| g.get_source() == "stick:GAMESTICK"), true);
+        assert_eq!(library.find_ejectable(None), Some(String::from("stick:GAMESTICK")));
+    }
+
+    #[test]
+    fn ut_refresh_removed_source() {
+        let dir = std::env::temp_dir().join(format!("goco-source-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut library = Library::new();
+        library.push(Directory::new(dir.clone()));
+        assert_eq!(library.refresh().len(), 0);
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("testenv/GAMESTICK/Pong.pck", dir.join("Pong.pck")).unwrap();
+        assert_eq!(library.has_arrivals(), true);
+        match library.refresh().as_slice() {
+            [Change::Added(_, count)] => assert_eq!(*count, 1),
+            changes => panic!("unexpected changes {:?}", changes),
+        }
+        assert_eq!(library.is_available(&library.get_games()[0]), true);
+
+        // a copied pack is found when the source is read again
+        let id = library.get_loaded_sources()[0].get_id();
+        std::fs::copy("testenv/GAMESTICK/Super Platformer.pck", dir.join("Super Platformer.pck")).unwrap();
+        let (games, report) = library.read(&id).unwrap();
+        assert_eq!(library.update(&id, games.clone(), report.clone()), true);
+        assert_eq!(library.update(&id, games, report), false);
+        assert_eq!(library.get_games().len(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        match library.refresh().as_slice() {
+            [Change::Removed(_)] => (),
+            changes => panic!("unexpected changes {:?}", changes),
+        }
+        assert_eq!(library.get_games().len(), 0);
+    }
+
+    #[test]
+    fn ut_prefer_newest_copy() {
+        let dir = std::env::temp_dir().join(format!("goco-copies-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("backup")).unwrap();
+        std::fs::create_dir_all(dir.join("v2")).unwrap();
+        std::fs::copy("testenv/GAMESTICK/Pong.pck", dir.join("Pong.pck")).unwrap();
+        std::fs::copy("testenv/GAMESTICK/Pong.pck", dir.join("backup/Pong (copy).pck")).unwrap();
+        std::fs::write(dir.join("Pong.cfg"), "[game]\nversion = 1.9\n").unwrap();
+        // a newer export of the same project
+        std::fs::copy("testenv/GAMESTICK/Super Platformer.pck", dir.join("v2/Pong.pck")).unwrap();
+        std::fs::write(dir.join("v2/Pong.cfg"), "[game]\nname = Pong\nversion = 1.10\n").unwrap();
+
+        let mut library = Library::new();
+        library.push(Directory::new(dir.clone()));
+        library.refresh();
+        let games = library.get_games();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].get_pck(), &dir.join("v2/Pong.pck"));
+        // every copy can still be picked
+        let copies = library.get_copies(&games[0]);
+        assert_eq!(copies.len(), 3);
+        assert_eq!(copies[0], games[0]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// A source that is always available but can never be read.
+    #[derive(Debug)]
+    struct Broken(PathBuf);
+
+    impl LibrarySource for Broken {
+        fn get_id(&self) -> String {
+            String::from("broken")
+        }
+
+        fn get_kind(&self) -> SourceKind {
+            SourceKind::Directory
+        }
+
+        fn get_root(&self) -> &PathBuf {
+            &self.0
+        }
+
+        fn read_games(&self, _: &mut LibraryIndex, _: &mut Report) -> Result<Vec<Game>, GocoError> {
+            Err(GocoError::MountError(self.0.clone(), String::from("permission denied")))
+        }
+    }
+
+    #[test]
+    fn ut_skip_failed_source() {
+        let mut library = Library::new();
+        library.push(Broken(PathBuf::from("testenv/GAMESTICK")));
+        match library.refresh().as_slice() {
+            [Change::Failed(_)] => (),
+            changes => panic!("unexpected changes {:?}", changes),
+        }
+        // the failure is only reported once
+        assert_eq!(library.has_arrivals(), false);
+        assert_eq!(library.refresh().len(), 0);
+        // until the player asks to read the sources again
+        library.retry_failed();
+        assert_eq!(library.get_arrivals(), vec![String::from("broken")]);
+        assert_eq!(library.refresh().len(), 1);
+    }
+}
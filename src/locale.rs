@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use iced::Font;
+
+use crate::config::Config;
+use crate::theme::load_font;
+
+/// The directory under `GOCO_ROOT` where message catalogs are stored.
+pub const LOCALES_DIR: &str = "locales";
+
+/// The file extension for message catalogs.
+pub const LOCALE_EXT: &str = "cfg";
+
+/// The language code of the built-in catalog used when a message is missing.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// The built-in English messages, which every other catalog falls back to.
+///
+/// Placeholders written as `{name}` are filled in by [Locales::format].
+const ENGLISH: &[(&str, &str)] = &[
+    ("insert-gamestick", "Please Insert GAMESTICK"),
+    ("play", "PLAY"),
+    ("settings", "Settings"),
+    ("setting-theme", "Theme"),
+    ("setting-language", "Language"),
+    ("setting-reduce-motion", "Reduce Motion"),
+    ("on", "On"),
+    ("off", "Off"),
+    ("copying", "Copying {name} ... {percent}%"),
+    ("launching", "Starting {name} ..."),
+    ("now-playing", "Now Playing"),
+    ("quit-hint", "Press Home to return to the library"),
+    ("details", "DETAILS"),
+    ("install", "Install"),
+    ("uninstall", "Uninstall"),
+    ("back", "Back"),
+    ("play-copy", "Play {path}"),
+    ("please-wait", "Please wait ..."),
+    ("source-internal", "Installed"),
+    ("source-stick", "GAMESTICK"),
+    ("source-directory", "Shared"),
+    ("game-size", "Size: {size}"),
+    ("storage-used", "Internal storage used: {size}"),
+    ("game-installed", "{name} was installed"),
+    ("game-uninstalled", "{name} was uninstalled"),
+    ("refresh-library", "REFRESH"),
+    ("library-refreshing", "Refreshing the library ..."),
+    ("diagnostics", "DIAGNOSTICS"),
+    ("library-diagnostics", "Library diagnostics"),
+    ("no-problems", "Every file in the library was read without problems"),
+    ("diagnostics-hint", "{count} file(s) were skipped — press Down to see why"),
+    ("diagnostics-page", "{first}–{last} of {count}"),
+    ("problem-wrong-extension", "Wrong extension"),
+    ("problem-unreadable", "Unreadable"),
+    ("problem-corrupt-pack", "Corrupt pack"),
+    ("problem-missing-icon", "Missing icon"),
+    ("problem-unsupported-engine", "Unsupported engine"),
+    ("stick-removed", "GAMESTICK removed — progress may be lost"),
+    ("stick-removed-hint", "Insert the GAMESTICK to keep playing"),
+    ("stick-paused", "GAMESTICK removed — {name} is paused"),
+    ("stick-paused-hint", "Reinsert the GAMESTICK within {seconds} seconds to continue"),
+    ("godot-missing", "Godot was not found at {path}"),
+    ("spawn-failed", "Failed to run {program}: {reason}"),
+    ("game-running", "A game is already running"),
+    ("launch-timeout", "{name} did not start within {seconds} seconds"),
+    ("game-exited", "{name} closed unexpectedly: {status}"),
+    ("mount-error", "Failed to access the drive at {path}: {reason}"),
+    ("permission-denied", "The console is not allowed to read {path}"),
+    ("invalid-pack", "{path} is not a valid game: {reason}"),
+    ("unsupported-engine", "{path} needs {engine}, which the console cannot run"),
+    ("not-executable", "{path} cannot be run: {reason}"),
+    ("eject-done", "The GAMESTICK can now be removed"),
+    ("gamepad-connected", "Gamepad connected: {name}"),
+    ("gamepad-disconnected", "Gamepad disconnected: {name}"),
+    ("gpio-failed", "Failed to set up the console's buttons: {reason}"),
+    ("cache-failed", "Failed to copy {path} to the cache: {reason}"),
+    ("install-failed", "Failed to install {path}: {reason}"),
+    ("settings-save-failed", "Failed to save settings: {reason}"),
+];
+
+/// The translated messages for a single language.
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    /// The language's code, such as `es`.
+    code: String,
+    /// The language's name written in the language itself.
+    name: String,
+    messages: HashMap<String, String>,
+    /// A font covering the language's script.
+    font: Option<Font>,
+}
+
+impl Catalog {
+    /// Creates the built-in English catalog.
+    pub fn english() -> Self {
+        Self {
+            code: String::from(DEFAULT_LANGUAGE),
+            name: String::from("English"),
+            messages: ENGLISH
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            font: None,
+        }
+    }
+
+    /// Reads a catalog from the file at `path`.
+    ///
+    /// The file's name (without extension) is the language's code. Messages are
+    /// stored in the `[messages]` table, and the path to a font is relative to
+    /// the file's directory.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let config = Config::load(path)?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let code = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        Ok(Self {
+            name: match config.get("locale", "name") {
+                Some(n) => n.to_string(),
+                None => code.clone(),
+            },
+            code: code,
+            messages: match config.get_section("messages") {
+                Some(table) => table.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+                None => HashMap::new(),
+            },
+            font: match config.get("locale", "font") {
+                Some(f) => Some(load_font(&dir.join(f))?),
+                None => None,
+            },
+        })
+    }
+
+    pub fn get_code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_font(&self) -> Option<Font> {
+        self.font
+    }
+
+    /// Accesses the message stored under `key`, if this catalog translates it.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(|m| m.as_str())
+    }
+}
+
+/// The collection of available languages and the one currently in use.
+#[derive(Debug, Clone)]
+pub struct Locales {
+    list: Vec<Catalog>,
+    current: usize,
+}
+
+impl Locales {
+    /// Loads the built-in English catalog followed by every catalog found in
+    /// `dir`, and selects the language coded `selected` if it exists.
+    ///
+    /// A catalog file named after the default language extends the built-in
+    /// English messages instead of being listed twice.
+    pub fn load(dir: &Path, selected: Option<&str>) -> Self {
+        let mut list = vec![Catalog::english()];
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().map_or(false, |ext| ext == LOCALE_EXT))
+                .collect();
+            paths.sort();
+            for path in paths {
+                match Catalog::load(&path) {
+                    Ok(c) if c.get_code() == DEFAULT_LANGUAGE => list[0].messages.extend(c.messages),
+                    Ok(c) => list.push(c),
+                    Err(e) => log::error!("Failed to load language: {}", e),
+                }
+            }
+        }
+        let mut locales = Self { list: list, current: 0 };
+        if let Some(code) = selected {
+            locales.select(code);
+        }
+        locales
+    }
+
+    /// Switches to the language coded `code`.
+    ///
+    /// Returns `false` if no language has the code.
+    pub fn select(&mut self, code: &str) -> bool {
+        match self.list.iter().position(|c| c.get_code().eq_ignore_ascii_case(code)) {
+            Some(i) => {
+                self.current = i;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Switches to the next language in the list, or the previous one when
+    /// `forward` is `false`. The list wraps around at both ends.
+    pub fn cycle(&mut self, forward: bool) {
+        let len = self.list.len();
+        self.current = match forward {
+            true => (self.current + 1) % len,
+            false => (self.current + len - 1) % len,
+        };
+    }
+
+    /// References the language currently in use.
+    pub fn get_current(&self) -> &Catalog {
+        &self.list[self.current]
+    }
+
+    /// Accesses the message stored under `key` in the current language.
+    ///
+    /// Falls back to English when the current language does not translate the
+    /// message, and to the key itself when no catalog has it.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.get_current()
+            .get(key)
+            .or_else(|| self.list[0].get(key))
+            .unwrap_or(key)
+    }
+
+    /// Accesses the message stored under `key` in the current language and
+    /// replaces each `{name}` placeholder with its value from `args`.
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        args.iter().fold(self.get(key).to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{}}}", name), value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_fallback_to_english() {
+        let mut locales = Locales::load(Path::new("testenv/locales"), Some("es"));
+        assert_eq!(locales.get_current().get_name(), "Español");
+        assert_eq!(locales.get("play"), "JUGAR");
+        // untranslated messages are read from the english catalog
+        assert_eq!(locales.get("setting-language"), "Language");
+        assert_eq!(locales.get("missing-key"), "missing-key");
+        locales.cycle(true);
+        assert_eq!(locales.get_current().get_code(), DEFAULT_LANGUAGE);
+        assert_eq!(locales.get("play"), "PLAY");
+    }
+
+    #[test]
+    fn ut_format_placeholders() {
+        let mut locales = Locales::load(Path::new("testenv/locales"), Some("es"));
+        assert_eq!(locales.format("greeting", &[("name", "Pong")]), "¡Hola, Pong!");
+        assert_eq!(locales.select("xx"), false);
+        assert_eq!(locales.get_current().get_code(), "es");
+    }
+}
@@ -0,0 +1,484 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::config::Config;
+use crate::env;
+
+/// The directory under `GOCO_ROOT` where log files are stored.
+pub const LOGS_DIR: &str = "logs";
+
+/// The name of the log file currently being written.
+pub const LOG_FILE: &str = "goco.log";
+
+/// The level used when no level is configured.
+pub const DEFAULT_LEVEL: &str = "info";
+
+/// The size a log file may grow to before it is rotated (in bytes).
+const DEFAULT_MAX_SIZE: u64 = 1024 * 1024;
+
+/// The number of rotated log files kept next to the current one.
+const DEFAULT_MAX_FILES: usize = 3;
+
+/// The name of this crate's modules in a record's target.
+const CRATE_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// The socket accepting messages in the systemd journal's native protocol.
+#[cfg(target_os = "linux")]
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// The levels allowed for each module, written like `info,os=debug,wgpu=warn`.
+///
+/// A directive without a module name sets the level for the whole console.
+/// Modules of the console can be named without the crate's prefix, so `os`
+/// matches `goco::os`. Other crates (such as the renderer) are limited to
+/// warnings unless they are named in a directive.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Filter {
+    level: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    pub fn new(level: LevelFilter) -> Self {
+        Self {
+            level: level,
+            modules: Vec::new(),
+        }
+    }
+
+    /// Parses the comma-separated directives in `spec`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = Self::new(LevelFilter::Info);
+        for directive in spec.split(',').map(|d| d.trim()).filter(|d| d.is_empty() == false) {
+            let (module, level) = match directive.split_once('=') {
+                Some((m, l)) => (Some(m.trim()), l.trim()),
+                None => (None, directive),
+            };
+            let level: LevelFilter = level
+                .parse()
+                .map_err(|_| format!("unknown log level '{}'", level))?;
+            match module {
+                None => filter.level = level,
+                Some(m) if m.is_empty() == true => return Err(format!("missing module name in '{}'", directive)),
+                Some(m) => filter.modules.push((m.to_string(), level)),
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Determines the most detailed level allowed for records from `target`.
+    ///
+    /// The directive naming the longest matching module wins.
+    pub fn get_level(&self, target: &str) -> LevelFilter {
+        let within = |m: &str, target: &str| {
+            target == m || target.strip_prefix(m).map_or(false, |rest| rest.starts_with("::"))
+        };
+        // a module of the console may be named without the crate's prefix
+        let matches = |m: &str| {
+            within(m, target) || target.strip_prefix(CRATE_NAME).and_then(|t| t.strip_prefix("::")).map_or(false, |t| within(m, t))
+        };
+        match self.modules.iter().filter(|(m, _)| matches(m)).max_by_key(|(m, _)| m.len()) {
+            Some((_, level)) => *level,
+            None if within(CRATE_NAME, target) == true => self.level,
+            None => self.level.min(LevelFilter::Warn),
+        }
+    }
+
+    /// Determines the most detailed level allowed for any module.
+    pub fn get_max(&self) -> LevelFilter {
+        self.modules.iter().map(|(_, l)| *l).fold(self.level, |a, b| a.max(b))
+    }
+}
+
+/// A log file that is moved aside once it grows too large.
+///
+/// Rotated files are numbered from newest to oldest (`goco.log.1`,
+/// `goco.log.2`, ...), and the oldest is removed when there are too many.
+#[derive(Debug)]
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    /// Opens the log file at `path` for appending, creating its directory if needed.
+    pub fn open(path: &Path, max_size: u64, max_files: usize) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            size: file.metadata()?.len(),
+            path: path.to_path_buf(),
+            file: file,
+            max_size: max_size,
+            max_files: max_files,
+        })
+    }
+
+    /// Appends the `line` to the file, rotating the file first if the line
+    /// would grow it past its maximum size.
+    pub fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 + 1 > self.max_size {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// Computes the path of the rotated file numbered `n`.
+    fn get_rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{}", n));
+        self.path.with_file_name(name)
+    }
+
+    /// Shifts every rotated file up by one and starts a new empty file.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        if self.max_files == 0 {
+            self.file.set_len(0)?;
+        } else {
+            let _ = std::fs::remove_file(self.get_rotated_path(self.max_files));
+            for n in (1..self.max_files).rev() {
+                let from = self.get_rotated_path(n);
+                if from.exists() == true {
+                    std::fs::rename(&from, self.get_rotated_path(n + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, self.get_rotated_path(1))?;
+            self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        }
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// A connection to the systemd journal.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct Journal {
+    socket: std::os::unix::net::UnixDatagram,
+}
+
+#[cfg(target_os = "linux")]
+impl Journal {
+    fn connect() -> std::io::Result<Self> {
+        let socket = std::os::unix::net::UnixDatagram::unbound()?;
+        socket.connect(JOURNALD_SOCKET)?;
+        Ok(Self { socket: socket })
+    }
+
+    /// Sends the `record` as a journal entry with its syslog priority.
+    fn send(&self, record: &Record) {
+        let priority = match record.level() {
+            Level::Error => "3",
+            Level::Warn => "4",
+            Level::Info => "6",
+            Level::Debug | Level::Trace => "7",
+        };
+        let mut entry = Vec::new();
+        Self::append_field(&mut entry, "PRIORITY", priority);
+        Self::append_field(&mut entry, "SYSLOG_IDENTIFIER", CRATE_NAME);
+        Self::append_field(&mut entry, "TARGET", record.target());
+        Self::append_field(&mut entry, "MESSAGE", &record.args().to_string());
+        // the journal is best-effort and never interrupts the console
+        let _ = self.socket.send(&entry);
+    }
+
+    /// Writes a `name=value` field, switching to the length-prefixed form for
+    /// values spanning multiple lines.
+    fn append_field(entry: &mut Vec<u8>, name: &str, value: &str) {
+        entry.extend_from_slice(name.as_bytes());
+        match value.contains('\n') {
+            true => {
+                entry.push(b'\n');
+                entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+                entry.extend_from_slice(value.as_bytes());
+            }
+            false => {
+                entry.push(b'=');
+                entry.extend_from_slice(value.as_bytes());
+            }
+        }
+        entry.push(b'\n');
+    }
+}
+
+/// The console's logger, writing each record to the terminal and optionally
+/// to a rotating log file and the systemd journal.
+#[derive(Debug)]
+pub struct Logger {
+    filter: Filter,
+    file: Option<Mutex<RotatingFile>>,
+    #[cfg(target_os = "linux")]
+    journal: Option<Journal>,
+}
+
+impl Logger {
+    /// Installs the logger configured by the `[log]` table of `config`.
+    ///
+    /// The `GOCO_LOG_LEVEL` environment variable takes priority over the
+    /// configured level. Records are only written to the log file under
+    /// `GOCO_ROOT/logs` when `to_file` is set.
+    pub fn init(config: &Config, to_file: bool) {
+        let spec = match std::env::var(env::GOCO_LOG_LEVEL) {
+            Ok(s) => s,
+            Err(_) => config.get("log", "level").unwrap_or(DEFAULT_LEVEL).to_string(),
+        };
+        let (filter, bad_spec) = match Filter::parse(&spec) {
+            Ok(f) => (f, None),
+            Err(e) => (Filter::new(LevelFilter::Info), Some(e)),
+        };
+
+        let mut file_error = None;
+        let file = match to_file == true && config.get_bool("log", "file") != Some(false) {
+            true => {
+                let max_size = config
+                    .get("log", "max-size")
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .map_or(DEFAULT_MAX_SIZE, |kib| kib * 1024);
+                let max_files = config
+                    .get("log", "max-files")
+                    .and_then(|s| s.parse::<usize>().ok())
+                    .unwrap_or(DEFAULT_MAX_FILES);
+                let path = env::root().join(LOGS_DIR).join(LOG_FILE);
+                match RotatingFile::open(&path, max_size, max_files) {
+                    Ok(f) => Some(Mutex::new(f)),
+                    Err(e) => {
+                        file_error = Some(format!("{:?}: {}", path, e));
+                        None
+                    }
+                }
+            }
+            false => None,
+        };
+
+        #[cfg(target_os = "linux")]
+        let mut journal_error = None;
+        #[cfg(target_os = "linux")]
+        let journal = match config.get_bool("log", "journald") == Some(true) {
+            true => match Journal::connect() {
+                Ok(j) => Some(j),
+                Err(e) => {
+                    journal_error = Some(e.to_string());
+                    None
+                }
+            },
+            false => None,
+        };
+
+        let max = filter.get_max();
+        let logger = Self {
+            filter: filter,
+            file: file,
+            #[cfg(target_os = "linux")]
+            journal: journal,
+        };
+        // a logger can only be installed once per process
+        if log::set_boxed_logger(Box::new(logger)).is_err() == true {
+            return;
+        }
+        log::set_max_level(max);
+
+        if let Some(e) = bad_spec {
+            log::warn!("Ignoring log level {:?}: {}", spec, e);
+        }
+        if let Some(e) = file_error {
+            log::warn!("Failed to open log file {}", e);
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(e) = journal_error {
+            log::warn!("Failed to connect to the systemd journal: {}", e);
+        }
+    }
+
+    /// Writes the `record` as a single line with its time, level and module.
+    fn format(record: &Record) -> String {
+        format!(
+            "{} {:<5} {}: {}",
+            format_timestamp(SystemTime::now()),
+            record.level(),
+            record.target(),
+            record.args()
+        )
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.get_level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) == false {
+            return;
+        }
+        let line = Self::format(record);
+        eprintln!("{}", line);
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                let _ = file.write_line(&line);
+            }
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(journal) = &self.journal {
+            journal.send(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                let _ = file.file.flush();
+            }
+        }
+    }
+}
+
+/// Writes the `time` in UTC as `YYYY-MM-DDTHH:MM:SS.mmmZ`.
+pub fn format_timestamp(time: SystemTime) -> String {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since.as_secs();
+    let (days, rem) = ((secs / 86400) as i64, secs % 86400);
+    // convert the days since 1970-01-01 into a civil date
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        since.subsec_millis()
+    )
+}
+
+/// Limits a repeated message to once per period, counting the repeats in between.
+#[derive(Debug, PartialEq)]
+pub struct Throttle {
+    period: Duration,
+    /// The time the message was last let through.
+    last: Option<Instant>,
+    suppressed: u64,
+}
+
+impl Throttle {
+    pub const fn new(period: Duration) -> Self {
+        Self {
+            period: period,
+            last: None,
+            suppressed: 0,
+        }
+    }
+
+    /// Checks if the message may be logged at the time `now`.
+    ///
+    /// Returns the number of repeats suppressed since the message was last
+    /// logged, or `None` if this repeat is suppressed too.
+    pub fn check(&mut self, now: Instant) -> Option<u64> {
+        match self.last {
+            Some(last) if now.saturating_duration_since(last) < self.period => {
+                self.suppressed += 1;
+                None
+            }
+            _ => {
+                self.last = Some(now);
+                Some(std::mem::take(&mut self.suppressed))
+            }
+        }
+    }
+}
+
+/// Logs a message at most once every `period` from the calling line, noting
+/// how many repeats were left out.
+///
+/// Used for messages written on every tick of a subscription.
+macro_rules! throttled {
+    ($period:expr, $level:expr, $($arg:tt)+) => {{
+        static THROTTLE: std::sync::Mutex<$crate::logger::Throttle> =
+            std::sync::Mutex::new($crate::logger::Throttle::new($period));
+        if log::log_enabled!($level) == true {
+            if let Some(suppressed) = THROTTLE.lock().unwrap().check(std::time::Instant::now()) {
+                match suppressed {
+                    0 => log::log!($level, $($arg)+),
+                    n => log::log!($level, "{} ({} repeats suppressed)", format_args!($($arg)+), n),
+                }
+            }
+        }
+    }};
+}
+
+pub(crate) use throttled;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_filter_modules() {
+        let filter = Filter::parse("warn, os=debug, goco::os::menu=error, wgpu_core=info").unwrap();
+        assert_eq!(filter.get_level("goco::engine"), LevelFilter::Warn);
+        assert_eq!(filter.get_level("goco::os"), LevelFilter::Debug);
+        assert_eq!(filter.get_level("goco::os::menu"), LevelFilter::Error);
+        assert_eq!(filter.get_level("goco::osx"), LevelFilter::Warn);
+        assert_eq!(filter.get_level("wgpu_core::device"), LevelFilter::Info);
+        // other crates are limited to warnings
+        assert_eq!(Filter::parse("trace").unwrap().get_level("naga"), LevelFilter::Warn);
+        assert_eq!(filter.get_max(), LevelFilter::Debug);
+        assert_eq!(Filter::parse("loud").is_err(), true);
+        assert_eq!(Filter::parse("=info").is_err(), true);
+    }
+
+    #[test]
+    fn ut_throttle() {
+        let now = Instant::now();
+        let mut throttle = Throttle::new(Duration::from_secs(10));
+        assert_eq!(throttle.check(now), Some(0));
+        assert_eq!(throttle.check(now + Duration::from_secs(1)), None);
+        assert_eq!(throttle.check(now + Duration::from_secs(2)), None);
+        assert_eq!(throttle.check(now + Duration::from_secs(10)), Some(2));
+        assert_eq!(throttle.check(now + Duration::from_secs(20)), Some(0));
+    }
+
+    #[test]
+    fn ut_rotating_file() {
+        let dir = std::env::temp_dir().join(format!("goco-logs-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join(LOG_FILE);
+        let mut file = RotatingFile::open(&path, 16, 2).unwrap();
+        for line in ["first line", "second line", "third line", "fourth line"] {
+            file.write_line(line).unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fourth line\n");
+        assert_eq!(std::fs::read_to_string(dir.join("goco.log.1")).unwrap(), "third line\n");
+        assert_eq!(std::fs::read_to_string(dir.join("goco.log.2")).unwrap(), "second line\n");
+        // the oldest file is removed
+        assert_eq!(dir.join("goco.log.3").exists(), false);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn ut_format_timestamp() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        assert_eq!(format_timestamp(time), "2023-11-14T22:13:20.123Z");
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    }
+}
@@ -0,0 +1,86 @@
+use crate::config::Config;
+
+/// The window width the console's layout was designed for.
+const REFERENCE_WIDTH: f32 = 1920.0;
+/// The window height the console's layout was designed for.
+const REFERENCE_HEIGHT: f32 = 1080.0;
+
+/// The smallest user-configurable scale factor.
+const MIN_SCALE: f32 = 0.25;
+/// The largest user-configurable scale factor.
+const MAX_SCALE: f32 = 4.0;
+
+/// Converts sizes designed for a 1920x1080 window into sizes for the actual
+/// window, so the layout fits any resolution.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Metrics {
+    width: f32,
+    height: f32,
+    /// The user's scale factor applied on top of the window's scale.
+    scale: f32,
+}
+
+impl Metrics {
+    /// Creates new [Metrics] for a window of `width` x `height` pixels.
+    pub fn new(width: u32, height: u32, scale: f32) -> Self {
+        Self {
+            width: width.max(1) as f32,
+            height: height.max(1) as f32,
+            scale: scale.clamp(MIN_SCALE, MAX_SCALE),
+        }
+    }
+
+    /// Creates new [Metrics] using the scale factor from the `[display]` table
+    /// of the configuration file.
+    pub fn from_config(width: u32, height: u32, config: &Config) -> Self {
+        let scale = config
+            .get("display", "scale")
+            .and_then(|v| v.parse::<f32>().ok())
+            .unwrap_or(1.0);
+        Self::new(width, height, scale)
+    }
+
+    /// Updates the window's size after it was resized.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        *self = Self::new(width, height, self.scale);
+    }
+
+    /// Computes the factor to multiply every reference size by.
+    ///
+    /// The smaller of the horizontal and vertical ratios is used so the layout
+    /// never overflows the window along either axis.
+    pub fn factor(&self) -> f32 {
+        (self.width / REFERENCE_WIDTH).min(self.height / REFERENCE_HEIGHT) * self.scale
+    }
+
+    /// Converts a size in reference pixels to a size in window pixels.
+    pub fn scale(&self, size: f32) -> u16 {
+        (size * self.factor()).round().clamp(1.0, u16::MAX as f32) as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_scale_to_window() {
+        assert_eq!(Metrics::new(1920, 1080, 1.0).scale(256.0), 256);
+        // 720p televisions shrink the layout
+        assert_eq!(Metrics::new(1280, 720, 1.0).scale(384.0), 256);
+        // 4K televisions enlarge the layout
+        assert_eq!(Metrics::new(3840, 2160, 1.0).scale(256.0), 512);
+        // the narrower axis limits the layout
+        assert_eq!(Metrics::new(960, 1080, 1.0).scale(256.0), 128);
+    }
+
+    #[test]
+    fn ut_user_scale() {
+        let mut metrics = Metrics::new(1920, 1080, 1.5);
+        assert_eq!(metrics.scale(100.0), 150);
+        metrics.resize(1280, 720);
+        assert_eq!(metrics.scale(100.0), 100);
+        // sizes never collapse to zero
+        assert_eq!(Metrics::new(1, 1, 0.0).scale(10.0), 1);
+    }
+}
\ No newline at end of file
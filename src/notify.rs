@@ -0,0 +1,182 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The most toasts shown on screen at once.
+const MAX_TOASTS: usize = 3;
+
+/// How important a notice is to the player.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// The time a notice of this severity stays on screen.
+    pub fn get_lifetime(&self) -> Duration {
+        match self {
+            Self::Info => Duration::from_secs(3),
+            Self::Warning => Duration::from_secs(5),
+            Self::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+/// An event raised by any part of the console to be shown to the player.
+///
+/// The message is written by looking up `key` in the message catalog and
+/// filling in its placeholders with `args`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Notice {
+    severity: Severity,
+    key: &'static str,
+    args: Vec<(&'static str, String)>,
+}
+
+impl Notice {
+    pub fn new(severity: Severity, key: &'static str) -> Self {
+        Self {
+            severity: severity,
+            key: key,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn info(key: &'static str) -> Self {
+        Self::new(Severity::Info, key)
+    }
+
+    pub fn warning(key: &'static str) -> Self {
+        Self::new(Severity::Warning, key)
+    }
+
+    pub fn error(key: &'static str) -> Self {
+        Self::new(Severity::Error, key)
+    }
+
+    /// Sets the value filled in for the placeholder `{name}`.
+    pub fn with<T: ToString>(mut self, name: &'static str, value: T) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    pub fn get_severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn get_key(&self) -> &'static str {
+        self.key
+    }
+
+    /// Accesses the placeholder values in the form accepted by [crate::locale::Locales::format].
+    pub fn get_args(&self) -> Vec<(&str, &str)> {
+        self.args.iter().map(|(k, v)| (*k, v.as_str())).collect()
+    }
+}
+
+/// The notices raised since the console last checked, shared by every thread.
+static NOTICES: Mutex<VecDeque<Notice>> = Mutex::new(VecDeque::new());
+
+/// Queues the `notice` to be shown on screen.
+pub fn raise(notice: Notice) {
+    NOTICES.lock().unwrap().push_back(notice);
+}
+
+/// Removes and returns all notices raised since the last call.
+pub fn drain() -> Vec<Notice> {
+    NOTICES.lock().unwrap().drain(..).collect()
+}
+
+/// The notices currently shown on screen, each with its remaining time.
+#[derive(Debug, PartialEq)]
+pub struct Toasts {
+    list: VecDeque<(Notice, Duration)>,
+    /// The time of the previous tick.
+    last: Option<Instant>,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self {
+            list: VecDeque::new(),
+            last: None,
+        }
+    }
+
+    /// Shows the `notice`, removing the oldest toast if there are too many.
+    ///
+    /// A notice that is already shown has its time restarted instead of being
+    /// shown twice.
+    pub fn push(&mut self, notice: Notice) {
+        self.list.retain(|(n, _)| n != &notice);
+        let lifetime = notice.get_severity().get_lifetime();
+        self.list.push_back((notice, lifetime));
+        while self.list.len() > MAX_TOASTS {
+            self.list.pop_front();
+        }
+    }
+
+    /// Counts down the toasts' remaining time to `now` and removes expired toasts.
+    pub fn tick(&mut self, now: Instant) {
+        let delta = match self.last {
+            Some(last) => now.saturating_duration_since(last),
+            None => Duration::ZERO,
+        };
+        self.last = Some(now);
+        for (_, remaining) in self.list.iter_mut() {
+            *remaining = remaining.saturating_sub(delta);
+        }
+        self.list.retain(|(_, remaining)| remaining.is_zero() == false);
+    }
+
+    /// Removes every toast from the screen.
+    pub fn dismiss(&mut self) {
+        self.list.clear();
+    }
+
+    /// Iterates through the shown notices from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &Notice> {
+        self.list.iter().map(|(n, _)| n)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_raise_and_drain() {
+        raise(Notice::error("test-notice").with("path", "/usr/bin/godot"));
+        // other tests may raise notices at the same time
+        let notices: Vec<Notice> = drain().into_iter().filter(|n| n.get_key() == "test-notice").collect();
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].get_args(), vec![("path", "/usr/bin/godot")]);
+        assert_eq!(drain().iter().any(|n| n.get_key() == "test-notice"), false);
+    }
+
+    #[test]
+    fn ut_toasts_expire() {
+        let now = Instant::now();
+        let mut toasts = Toasts::new();
+        toasts.tick(now);
+        toasts.push(Notice::info("a"));
+        toasts.push(Notice::error("b"));
+        // repeated notices are not shown twice
+        toasts.push(Notice::info("a"));
+        assert_eq!(toasts.iter().map(|n| n.get_key()).collect::<Vec<_>>(), vec!["b", "a"]);
+        toasts.tick(now + Severity::Info.get_lifetime());
+        assert_eq!(toasts.iter().map(|n| n.get_key()).collect::<Vec<_>>(), vec!["b"]);
+        for key in ["c", "d", "e"] {
+            toasts.push(Notice::warning(key));
+        }
+        assert_eq!(toasts.iter().count(), MAX_TOASTS);
+        toasts.dismiss();
+        assert_eq!(toasts.is_empty(), true);
+    }
+}
@@ -1,30 +1,82 @@
 use iced::event::Event;
 use iced::executor;
-use iced::keyboard::KeyCode;
+use iced::mouse;
+use iced::touch;
 use iced::subscription;
 use iced::window;
 use std::time::{Duration, Instant};
 use iced::time;
 use iced::keyboard::Event::KeyPressed;
-use iced::{Alignment, Application, Command, Element, Length, Subscription, Theme};
-use iced::widget::{button, Container};
+use iced::{Alignment, Application, Color, Command, ContentFit, Element, Font, Length, Subscription, Theme};
+use iced::widget::{button, container, image, progress_bar, Container};
 use iced::widget::text;
+use iced::theme;
+use std::future::Future;
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+use iced::futures::channel::oneshot;
 
+use crate::cache::{Cache, CopyJob};
 use crate::engine::Engine;
-use crate::game::Game;
-use crate::gamestick::GameStick;
+use crate::focus;
+use crate::error::GocoError;
+use crate::game::{self, Game};
+use crate::pack::PackKind;
+use crate::install::{self, InternalLibrary};
+use crate::library::{Change, Library, LibrarySource, SourceKind};
+use crate::details::{Choice, Details};
+use crate::diagnostics::Report;
+use crate::watch;
 use crate::animator::Animation;
+use crate::config::Config;
+use crate::input::{Action, Bindings};
+use crate::gamepad;
+use crate::pointer;
+use crate::pointer::Pointer;
+use crate::metrics::Metrics;
+use crate::theme::{Dot, Themes, THEMES_DIR};
+use crate::settings::{Menu, Setting};
+use crate::locale::{Locales, LOCALES_DIR};
+use crate::layers::Layers;
+use crate::tap::Tap;
+use crate::tween::{ShelfMotion, TWEEN_TICK};
+use crate::notify::{self, Notice, Severity, Toasts};
+use crate::logger::throttled;
 
 #[cfg(feature = "rpi")]
 use crate::gpio::Io;
 
+/// The window's initial size before entering fullscreen mode.
+pub const WINDOW_SIZE: (u32, u32) = (1024, 768);
+
+/// The window's title, which is also used to find the window on X11.
+pub const WINDOW_TITLE: &str = "GOCO";
+
+/// The shortest time between repeats of a message logged on every tick.
+const SCAN_LOG_PERIOD: Duration = Duration::from_secs(30);
+
+/// The default time to wait for the engine to start a game (in seconds).
+const LAUNCH_TIMEOUT: u64 = 30;
+
+/// The time the GAMESTICK removal screen is shown before returning to the insert screen.
+const REMOVED_SCREEN_TIME: Duration = Duration::from_secs(8);
+
+/// The number of dots drawn in the loading spinner.
+const SPINNER_DOTS: usize = 8;
+
+/// The time for the spinner's lit dot to move to the next dot.
+const SPINNER_STEP: Duration = Duration::from_millis(100);
+
+/// The most screenshots shown on a bundled game's details screen.
+const MAX_SCREENSHOTS: usize = 3;
+
 // model the state of the application
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct Os {
     /// The backend Godot game engine to invoke for playing games.
     engine: Engine,
-    /// The available media drive.
-    drive: GameStick,
+    /// The places games are read from, such as the GAMESTICK.
+    sources: Library,
     /// List of all available loaded games.
     library: Vec<Game>,
     /// Determine the current selected game in the list.
@@ -36,45 +88,175 @@ pub struct Os {
     io: Io,
     /// Create an attribute for the animation player
     insert_animation: Animation,
+    /// The logo animation shown on the boot splash.
+    boot_animation: Animation,
+    /// Map the keyboard's keys to console actions.
+    bindings: Bindings,
+    /// Track the mouse or finger pressed against the screen.
+    pointer: Pointer,
+    /// The background process hiding the idle mouse cursor.
+    cursor_hider: Option<Child>,
+    /// Scale the layout to the window's size.
+    metrics: Metrics,
+    /// Store the configuration to save changed settings.
+    config: Config,
+    /// The available themes for drawing the console.
+    themes: Themes,
+    /// The available languages for the console's messages.
+    locales: Locales,
+    /// The transitions played while moving through the library.
+    motion: ShelfMotion,
+    /// The notifications shown over the screen.
+    toasts: Toasts,
+    /// The settings menu when it is opened.
+    menu: Option<Menu>,
+    /// A setting was changed since the settings menu was opened.
+    settings_changed: bool,
+    /// The time to wait for the engine to start a game before giving up.
+    launch_timeout: Duration,
+    /// The time a game stays paused after the [GameStick] is removed, waiting
+    /// for it to be reinserted. The game is quit right away when zero.
+    removal_grace: Duration,
+    /// The faster storage games are copied to before launching, if enabled.
+    cache: Option<Arc<Mutex<Cache>>>,
+    /// The selected game's pack being copied into the cache.
+    copy: Option<CopyJob>,
+    /// The games installed to the console's internal storage.
+    internal: InternalLibrary,
+    /// The details screen when it is opened for the selected game.
+    details: Option<Details>,
+    /// Whether library sources are being read on a separate thread.
+    rescanning: bool,
+    /// The first problem shown on the library diagnostics screen when it is opened.
+    diagnostics: Option<usize>,
+}
+
+/// Logs the `changes` found while refreshing the library's sources, showing
+/// the sources that failed to be read to the player.
+/// 
+/// Returns `true` if any source's games were added or removed.
+fn report_changes(changes: Vec<Change>) -> bool {
+    let mut changed = false;
+    for change in changes {
+        match change {
+            Change::Added(id, count) => {
+                log::info!("Found {} game(s) in {:?} ...", count, id);
+                changed = true;
+            }
+            Change::Removed(id) => {
+                log::info!("Removing {:?} ...", id);
+                changed = true;
+            }
+            Change::Failed(e) => {
+                log::error!("{}", e);
+                notify::raise(e.to_notice());
+            }
+        }
+    }
+    changed
+}
+
+/// Runs `task` on its own thread so the window keeps drawing while it works.
+/// 
+/// The future resolves to `None` if the thread panics.
+fn background<T: Send + 'static>(task: impl FnOnce() -> T + Send + 'static) -> impl Future<Output = Option<T>> {
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(task());
+    });
+    async move { receiver.await.ok() }
 }
 
 #[derive(Debug, PartialEq)]
 enum State {
+    /// Show the boot splash while the console starts up.
+    Booting,
     /// Request the user to insert a game drive.
     Requesting,
     /// Read games from the game drive.
     Loading,
+    /// Copy the selected game's pack into the cache before launching it.
+    Copying(Game),
+    /// Wait for the engine to start the game, since the time it was selected.
+    Launching(Game, Instant),
+    /// The game is being played on the engine.
+    InGame(Game),
+    /// The game, or its copy into the cache, is paused since the time its [GameStick] was removed.
+    Paused(Game, Instant),
+    /// Explain the game was quit because its [GameStick] was removed, since the time it was quit.
+    Removed(Instant),
 }
 
 impl Os {
-    /// Constructs a new [Os] structure.
+    /// Constructs a new [Os] structure starting on the boot splash.
     /// 
-    /// Also immediately checks if a [GameStick] is entered to load games without initial delay.
-    pub fn new() -> Self {
+    /// Call [Os::start_boot] to read the games of every available library source.
+    pub fn new(config: &Config) -> Self {
         // configure the Pi's IO
         #[cfg(feature = "rpi")]
         let io = match Io::configure() {
             Ok(r) => r,
             Err(e) => {
-                eprintln!("error: {}", e);
+                log::error!("{}", e);
+                notify::raise(e.to_notice());
                 Io::new()
             }
         };
 
+        let themes = Themes::load(&crate::env::root().join(THEMES_DIR), config.get("display", "theme"));
+        let locales = Locales::load(&crate::env::root().join(LOCALES_DIR), config.get("display", "language"));
+
         let mut os = Self {
             engine: Engine::new(),
-            drive: GameStick::new(),
+            sources: Library::from_config(config),
             library: Vec::new(),
             count: 0,
-            state: State::Requesting,
-            insert_animation: Animation::new(),
+            state: State::Booting,
+            insert_animation: Animation::new(themes.get_current().get_insert_art()),
+            boot_animation: Animation::new(Some(&crate::env::root().join("assets/boot"))),
+            toasts: Toasts::new(),
+            bindings: Bindings::from_config(config),
+            pointer: Pointer::new(),
+            cursor_hider: None,
+            metrics: Metrics::from_config(WINDOW_SIZE.0, WINDOW_SIZE.1, config),
+            config: config.clone(),
+            themes: themes,
+            locales: locales,
+            motion: ShelfMotion::new(config.get_bool("display", "reduce-motion").unwrap_or(false)),
+            menu: None,
+            settings_changed: false,
+            launch_timeout: Duration::from_secs(
+                config
+                    .get("console", "launch-timeout")
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .unwrap_or(LAUNCH_TIMEOUT),
+            ),
+            removal_grace: Duration::from_secs(
+                config
+                    .get("console", "removal-grace")
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .unwrap_or(0),
+            ),
+            cache: Cache::from_config(config).map(|c| Arc::new(Mutex::new(c))),
+            copy: None,
+            internal: InternalLibrary::open(),
+            details: None,
+            rescanning: false,
+            diagnostics: None,
             #[cfg(feature = "rpi")]
             io: io,
         };
-        // check if game stick is already inserted on application start
-        if os.drive.exists() == true {
-            os.initialize_library();
+        // hide the idle mouse cursor while in fullscreen mode (disabled with a value of 0)
+        let idle_seconds = config
+            .get("display", "hide-cursor")
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(3);
+        if std::env::var_os(crate::env::GOCO_NO_FULLSCREEN).is_none() == true && idle_seconds > 0 {
+            os.cursor_hider = pointer::hide_idle_cursor(idle_seconds);
         }
+        // begin reading from connected gamepads
+        #[cfg(target_os = "linux")]
+        gamepad::listen();
         
         os
     }
@@ -107,6 +289,7 @@ impl Os {
         let able_to_shift = self.count + 1 < self.library.len();
         if able_to_shift == true {
             self.count += 1;
+            self.motion.start(1.0);
         }
         able_to_shift
     }
@@ -119,41 +302,209 @@ impl Os {
         // cap at 0
         if able_to_shift == true {
             self.count -= 1;
+            self.motion.start(-1.0);
         }
         able_to_shift
     }
 
-    /// Transitions from the `Requesting` state to the `Loading` State while
-    /// if the drive can successfully be read from the filesystem.
-    /// 
-    /// Returns `true` if the state transition occurs successfully and `false` otherwise.
-    fn initialize_library(&mut self) -> bool {
-        self.count = 0;
-        if self.drive.can_read_dir() == false { return false };
-        self.library = GameStick::load(self.drive.get_path());
-        self.state = State::Loading;
-        true
+    /// Scrolls the shelf by `steps` games, where positive values scroll right.
+    /// The shelf is left in place while an overlay hides it.
+    fn scroll_shelf(&mut self, steps: i32) {
+        if self.state != State::Loading || self.has_overlay() == true {
+            return;
+        }
+        for _ in 0..steps.abs() {
+            match steps > 0 {
+                true => self.shift_shelf_right(),
+                false => self.shift_shelf_left(),
+            };
+        }
+    }
+
+    /// Removes the games of every library source that is gone, then shows the
+    /// library or the insert screen if anything changed. The sources that
+    /// became available are read by [Os::rescan_sources].
+    fn refresh_sources(&mut self) {
+        if report_changes(self.sources.remove_departed()) == true {
+            self.watch_sources();
+            self.merge_library();
+            self.show_library();
+        }
+    }
+
+    /// Watches every loaded library source for games being added, changed or
+    /// removed while it stays inserted.
+    fn watch_sources(&self) {
+        for source in self.sources.get_loaded_sources() {
+            watch::watch(&source.get_id(), source.get_root());
+        }
+    }
+
+    /// Reads the games of the sources with the ids `ids` on a separate thread,
+    /// which reports back with [Message::LibraryRescanned]. A source that
+    /// cannot be read is reported back without any games.
+    fn rescan_sources(&mut self, ids: Vec<String>) -> Command<Message> {
+        if ids.is_empty() == true {
+            return Command::none();
+        }
+        self.rescanning = true;
+        let sources = self.sources.clone();
+        Command::perform(
+            background(move || {
+                ids.into_iter()
+                    // a source being removed is left to the next scan
+                    .filter(|id| sources.get_source(id).map_or(false, |s| s.is_available()))
+                    .map(|id| {
+                        log::debug!("Reading {:?} ...", id);
+                        match sources.read(&id) {
+                            Ok(read) => (id, Some(read)),
+                            Err(e) => {
+                                log::error!("{}", e);
+                                notify::raise(e.to_notice());
+                                (id, None)
+                            }
+                        }
+                    })
+                    .collect::<Vec<(String, Option<(Vec<Game>, Report)>)>>()
+            }),
+            |games| Message::LibraryRescanned(games.unwrap_or_default()),
+        )
+    }
+
+    /// Reads every loaded library source again, along with any source that
+    /// became available or failed to be read, when the player asks to refresh
+    /// the library.
+    fn refresh_all(&mut self) -> Command<Message> {
+        if self.rescanning == true {
+            return Command::none();
+        }
+        log::info!("Refreshing the library ...");
+        notify::raise(Notice::info("library-refreshing"));
+        self.sources.retry_failed();
+        self.refresh_sources();
+        let mut ids: Vec<String> = self
+            .sources
+            .get_loaded_sources()
+            .iter()
+            .map(|s| s.get_id())
+            .collect();
+        ids.extend(self.sources.get_arrivals());
+        self.rescan_sources(ids)
+    }
+
+    /// Adds or replaces the games of each source read by [Os::rescan_sources],
+    /// keeping the selected game in place. A source that became available but
+    /// could not be read is skipped until it reappears or the player refreshes.
+    fn finish_rescan(&mut self, games: Vec<(String, Option<(Vec<Game>, Report)>)>) {
+        self.rescanning = false;
+        let mut changed = false;
+        for (id, read) in games {
+            match read {
+                Some((games, report)) if self.sources.is_loaded(&id) == true => {
+                    if self.sources.update(&id, games, report) == true {
+                        log::info!("Updated the games of {:?}", id);
+                        changed = true;
+                    }
+                }
+                Some((games, report)) => {
+                    let count = games.len();
+                    if self.sources.add(&id, games, report) == true {
+                        log::info!("Found {} game(s) in {:?} ...", count, id);
+                        changed = true;
+                    }
+                }
+                None if self.sources.is_loaded(&id) == false => self.sources.mark_failed(&id),
+                None => (),
+            }
+        }
+        if changed == true {
+            self.watch_sources();
+            self.merge_library();
+            if self.state == State::Requesting || self.state == State::Loading {
+                self.show_library();
+            }
+        }
+    }
+
+    /// Replaces the library with the games merged from every loaded source,
+    /// keeping the selected game selected if it is still in the library, or
+    /// else selecting the game that took its place.
+    fn merge_library(&mut self) {
+        let selected = self.library.get(self.count).map(|g| g.get_pck().clone());
+        let games = self.sources.get_games();
+        self.count = selected
+            .and_then(|pck| games.iter().position(|g| g.get_pck() == &pck))
+            .unwrap_or(self.count.min(games.len().saturating_sub(1)));
+        self.library = games;
+        // the details screen follows its game to the copy left in the library
+        if let Some(details) = self.details.take() {
+            let name = details.get_game().get_pck().file_name();
+            if let Some(game) = self.library.iter().find(|g| g.get_pck().file_name() == name) {
+                self.details = Some(self.describe(game));
+            }
+        }
+    }
+
+    /// Shows the library, or the insert screen if the library is empty.
+    fn show_library(&mut self) {
+        self.state = match self.library.is_empty() {
+            true => State::Requesting,
+            false => State::Loading,
+        };
+    }
+
+    /// Reads the games of every available library source on a separate thread
+    /// while the boot splash is shown.
+    fn start_boot(&self) -> Command<Message> {
+        let mut sources = self.sources.clone();
+        Command::perform(
+            background(move || {
+                report_changes(sources.refresh());
+                sources
+            }),
+            Message::LibraryLoaded,
+        )
+    }
+
+    /// Leaves the boot splash once the library `sources` have been read, moving
+    /// to the library if any games were found or else to the insert screen.
+    fn finish_boot(&mut self, sources: Option<Library>) {
+        // the sources are read again on the next scan if reading them failed
+        if let Some(sources) = sources {
+            self.sources = sources;
+        }
+        self.watch_sources();
+        self.merge_library();
+        self.show_library();
     }
 
-    /// Transitions from the `Loading` state to the `Requesting` State while
-    /// unloading the game library.
+    /// Removes the games of the library source with the id `id` while keeping
+    /// the games of every other source, moving to the `Requesting` state if no
+    /// games are left.
     /// 
-    /// Returns `true` if the state transition occurs successfully and `false` otherwise.
-    fn flush_library(&mut self) -> bool {
-        self.library = Vec::new();
-        self.count = 0;
-        self.state = State::Requesting;
-        true
-    }
-
-    /// Safely ejects the USB GAMESTICK drive and cleans up the currently
-    /// loaded library.
-    fn remove_drive(&mut self) -> bool {
-        if self.drive.eject() == true {
-            self.flush_library()
-        } else {
-            false
+    /// Returns `true` if the source's games were in the library and `false` otherwise.
+    fn flush_library(&mut self, id: &str) -> bool {
+        let flushed = self.sources.unload(id);
+        self.merge_library();
+        self.show_library();
+        flushed
+    }
+
+    /// Safely ejects the removable source holding the selected game, or else
+    /// the first loaded removable source such as the USB GAMESTICK drive, and
+    /// cleans up its games from the library.
+    fn remove_drive(&mut self) -> Result<(), GocoError> {
+        let selected = self.library.get(self.count).map(|g| g.get_source().to_string());
+        let id = match self.sources.find_ejectable(selected.as_deref()) {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+        if let Some(source) = self.sources.get_source(&id) {
+            source.eject()?;
         }
+        notify::raise(Notice::info("eject-done"));
+        self.flush_library(&id);
+        Ok(())
     }
 
     /// Triggers the underlying operating system into 'suspend' mode. Before
@@ -164,36 +515,427 @@ impl Os {
     /// a low-power mode. Various triggers can resume the machine, among them 
     /// pressing a key or quickly pressing and releasing the power button.
     #[cfg(feature = "rpi")]
-    fn power_down(&mut self) -> bool {
+    fn power_down(&mut self) -> Result<(), GocoError> {
         self.io.disable_pwr_led();
         match std::process::Command::new("shutdown")
             .arg("-h")
             .arg("now")
             .spawn()
         {
-            Ok(_) => true,
+            Ok(_) => Ok(()),
             // restore the power LED status if the command failed
             Err(e) => {
-                eprintln!("error: {}", e);
                 self.io.enable_pwr_led();
-                false
+                Err(GocoError::SpawnFailed(String::from("shutdown"), e))
             },
         }
     }
 
+    /// Logs a failed operation's error and shows it to the player.
+    fn report(result: Result<(), GocoError>) {
+        if let Err(e) = result {
+            log::error!("{}", e);
+            notify::raise(e.to_notice());
+        }
+    }
+
     /// Invokes a command to quit the godot game engine process to essentially
     /// "return home".
-    #[cfg(feature = "rpi")]
-    fn quit_game(&mut self) -> bool {
-        self.engine.kill_game()
+    fn quit_game(&mut self) {
+        // a copy left running finishes in the background and stays cached
+        self.copy = None;
+        if let State::Paused(..) = self.state {
+            self.abandon_game(Instant::now());
+            return;
+        }
+        self.engine.kill_game();
+        self.return_to_library();
+    }
+
+    /// Checks if the library source of the game being launched or played is gone.
+    fn is_source_missing(&self) -> bool {
+        match &self.state {
+            State::Copying(game) | State::Launching(game, _) | State::InGame(game) | State::Paused(game, _) => {
+                self.sources.is_available(game) == false
+            }
+            _ => false,
+        }
     }
 
-    /// Invokes the engine to run the game at index `count` in the loaded game library.
+    /// Responds to the library source of the game being removed at the time
+    /// `now` while the game is launching or being played, such as the
+    /// [GameStick] being pulled out.
     /// 
-    /// The Godot game engine is called to spawn a new process.
-    fn select_game(&mut self) {
+    /// A running game, or a game still being copied into the cache, is paused
+    /// if it is allowed to wait for the [GameStick] to be reinserted. Otherwise
+    /// the game is quit.
+    fn handle_removal(&mut self, now: Instant) -> Result<(), GocoError> {
+        let game = match &self.state {
+            State::Copying(game) | State::Launching(game, _) | State::InGame(game) => game.clone(),
+            _ => return Ok(()),
+        };
+        // a game launched from the cache no longer needs its source
+        if let (Some(pack), Some(source)) = (self.engine.get_pack(), self.sources.get_source(game.get_source())) {
+            if pack.starts_with(source.get_root()) == false {
+                return Ok(());
+            }
+        }
+        log::warn!("{:?} removed while playing {:?} ...", game.get_source(), game.get_name());
+        match (&self.state, self.removal_grace.is_zero()) {
+            (State::Copying(_), false) => {
+                // the copy failed with its source, so it is started over once the source returns
+                self.copy = None;
+                self.state = State::Paused(game, now);
+                Ok(())
+            }
+            // the game is suspended before it reads from the missing drive
+            (State::InGame(_), false) => match self.engine.pause_game() {
+                Ok(()) => {
+                    self.state = State::Paused(game, now);
+                    Ok(())
+                }
+                Err(e) => {
+                    self.abandon_game(now);
+                    Err(e)
+                }
+            },
+            _ => {
+                self.abandon_game(now);
+                Ok(())
+            }
+        }
+    }
+
+    /// Quits the game whose [GameStick] was removed at the time `now`, unloads
+    /// the games of its source, and explains what happened.
+    fn abandon_game(&mut self, now: Instant) {
+        let source = match &self.state {
+            State::Copying(game) | State::Launching(game, _) | State::InGame(game) | State::Paused(game, _) => {
+                Some(game.get_source().to_string())
+            }
+            _ => None,
+        };
+        self.copy = None;
+        self.engine.kill_game();
+        if let Some(id) = source {
+            self.flush_library(&id);
+        }
+        self.state = State::Removed(now);
+    }
+
+    /// Checks on the paused game at the time `now`, continuing it (or copying
+    /// it again) if its [GameStick] was reinserted with the game still on it,
+    /// or giving up once it has waited too long.
+    fn check_paused(&mut self, now: Instant) -> Result<(), GocoError> {
+        let (game, since) = match &self.state {
+            State::Paused(game, since) => (game.clone(), *since),
+            _ => return Ok(()),
+        };
+        if self.sources.is_available(&game) == true && game.get_pck().is_file() == true {
+            log::info!("{:?} reinserted, resuming {:?} ...", game.get_source(), game.get_name());
+            match self.engine.is_paused() {
+                true => {
+                    self.state = State::InGame(game);
+                    if let Err(e) = self.engine.resume_game() {
+                        self.quit_game();
+                        return Err(e);
+                    }
+                }
+                false => self.start_game(game)?,
+            }
+        } else if now.saturating_duration_since(since) > self.removal_grace {
+            self.abandon_game(now);
+        }
+        Ok(())
+    }
+
+    /// Invokes the engine to run the game at index `count` in the loaded game library
+    /// and waits for it to start. Games can only be selected from the library.
+    fn select_game(&mut self) -> Result<(), GocoError> {
+        if self.state != State::Loading {
+            return Ok(());
+        }
         // guaranteed to have `count` as a valid index for game library vector
-        self.engine.play_game(self.library.get(self.count).unwrap());
+        let game = self.library.get(self.count).unwrap().clone();
+        self.start_game(game)
+    }
+
+    /// Launches the `game` on the engine.
+    /// 
+    /// When the cache is enabled, the game's pack is first copied into the cache
+    /// unless an unchanged copy is already there. A bundle's pack is always
+    /// extracted into the cache.
+    fn start_game(&mut self, game: Game) -> Result<(), GocoError> {
+        if let Some(bundle) = game.get_bundle() {
+            bundle.prepare_save();
+        }
+        // an executable is run where it is, since a RAM disk is usually mounted
+        // without permission to run programs
+        if game.get_kind() == PackKind::Embedded {
+            return self.launch_game(game.clone(), game.get_pck());
+        }
+        let cache = match (&self.cache, game.get_bundle().is_some()) {
+            (Some(c), _) => c.clone(),
+            (None, true) => Arc::new(Mutex::new(Cache::for_bundles(&self.config))),
+            (None, false) => return self.launch_game(game.clone(), game.get_pck()),
+        };
+        let cached = cache.lock().ok().and_then(|c| c.lookup(game.get_pck()));
+        match cached {
+            Some(pck) => self.launch_game(game, &pck),
+            None => {
+                log::info!("Copying {:?} to the cache ...", game.get_name());
+                self.copy = Some(CopyJob::start(cache, game.get_pck().clone()));
+                self.state = State::Copying(game);
+                Ok(())
+            }
+        }
+    }
+
+    /// Invokes the engine to run the `game` from the pack file `pck` and waits
+    /// for it to start.
+    /// 
+    /// The Godot game engine is called to spawn a new process.
+    fn launch_game(&mut self, game: Game, pck: &std::path::Path) -> Result<(), GocoError> {
+        self.engine.play_pack(pck, game.get_kind())?;
+        log::info!("Launching {:?} from {:?} ...", game.get_name(), pck);
+        self.state = State::Launching(game, Instant::now());
+        Ok(())
+    }
+
+    /// Launches the game being copied into the cache once the copy finishes.
+    /// 
+    /// The game is launched from the [GameStick] if the copy fails, unless
+    /// its pack is inside a bundle.
+    fn check_copy(&mut self) -> Result<(), GocoError> {
+        let game = match &self.state {
+            State::Copying(game) => game.clone(),
+            _ => return Ok(()),
+        };
+        let result = match self.copy.as_ref().and_then(|job| job.poll()) {
+            Some(r) => r,
+            None => return Ok(()),
+        };
+        self.copy = None;
+        match result {
+            Ok(pck) => self.launch_game(game, &pck),
+            // the engine cannot run a bundle without its pack being extracted
+            Err(e) if game.get_bundle().is_some() => {
+                self.show_library();
+                Err(e)
+            }
+            Err(e) => {
+                log::warn!("{}", e);
+                notify::raise(e.to_notice());
+                self.launch_game(game.clone(), game.get_pck())
+            }
+        }
+    }
+
+    /// Moves from the `Launching` state to the `InGame` state once the engine
+    /// has started the game.
+    fn enter_game(&mut self) {
+        if let State::Launching(game, _) = &self.state {
+            log::info!("Playing {:?} ...", game.get_name());
+            self.state = State::InGame(game.clone());
+        }
+    }
+
+    /// Checks if a game is launching, being played, or paused on the engine.
+    fn is_playing(&self) -> bool {
+        matches!(self.state, State::Copying(_) | State::Launching(..) | State::InGame(_) | State::Paused(..))
+    }
+
+    /// Brings the console's window back to the front after a game exits by
+    /// restoring fullscreen mode and requesting focus. On X11, the window is
+    /// also activated by its title in case the window manager ignores the request.
+    fn restore_window(&self) -> Command<Message> {
+        log::debug!("Restoring the console's window ...");
+        focus::activate_window(WINDOW_TITLE);
+        let mut commands = vec![window::gain_focus()];
+        if std::env::var_os(crate::env::GOCO_NO_FULLSCREEN).is_none() == true {
+            commands.insert(0, window::change_mode(window::Mode::Fullscreen));
+        }
+        Command::batch(commands)
+    }
+
+    /// Returns from a launched or running game to the library with the game
+    /// selected, or to the insert screen if the library is empty.
+    fn return_to_library(&mut self) {
+        let game = match &self.state {
+            State::Copying(game) | State::Launching(game, _) | State::InGame(game) | State::Paused(game, _) => game.clone(),
+            _ => return,
+        };
+        if let Some(i) = self.library.iter().position(|g| g == &game) {
+            self.count = i;
+        }
+        self.state = match self.library.is_empty() {
+            true => State::Requesting,
+            false => State::Loading,
+        };
+    }
+
+    /// Checks on the game started by the engine at the time `now`, entering the
+    /// game once it starts and returning to the library once it exits or takes
+    /// too long to start.
+    fn check_game(&mut self, now: Instant) -> Result<(), GocoError> {
+        if let State::Copying(_) = self.state {
+            // a copy cut short by its removed source is not launched from the source
+            if self.is_source_missing() == true {
+                return self.handle_removal(now);
+            }
+            return self.check_copy();
+        }
+        let (name, since) = match &self.state {
+            State::Launching(game, since) => (game.get_name().to_string(), Some(*since)),
+            State::InGame(game) => (game.get_name().to_string(), None),
+            _ => return Ok(()),
+        };
+        // the game may have crashed reading from its removed pack
+        if self.is_source_missing() == true {
+            return self.handle_removal(now);
+        }
+        // the game was quit from outside the console
+        if self.engine.is_in_game() == false {
+            self.return_to_library();
+            return Ok(());
+        }
+        let status = match self.engine.poll_game() {
+            Ok(status) => status,
+            Err(e) => {
+                self.return_to_library();
+                return Err(e);
+            }
+        };
+        match (status, since) {
+            (Some(status), _) => {
+                log::info!("Game {:?} exited with {}", name, status);
+                self.return_to_library();
+                if status.success() == false {
+                    return Err(GocoError::GameExited(name, status));
+                }
+            }
+            (None, Some(_)) if self.engine.has_started() == true => self.enter_game(),
+            (None, Some(since)) if now.saturating_duration_since(since) > self.launch_timeout => {
+                // a game still running may only be quiet, so it is only quit if it never opened a window
+                match self.engine.get_process_id().and_then(focus::has_window) {
+                    Some(false) => {
+                        self.quit_game();
+                        return Err(GocoError::LaunchTimeout(name, self.launch_timeout));
+                    }
+                    _ => {
+                        log::info!("Game {:?} is still running after {} seconds, assuming it started", name, self.launch_timeout.as_secs());
+                        self.enter_game();
+                    }
+                }
+            }
+            (None, _) => (),
+        }
+        Ok(())
+    }
+
+    /// Creates the details screen for the `game`, measuring the space it takes
+    /// and the space used by the internal library, and finding its other copies.
+    fn describe(&self, game: &Game) -> Details {
+        let copies = self
+            .sources
+            .get_copies(game)
+            .into_iter()
+            .filter(|g| g.get_pck() != game.get_pck())
+            .collect();
+        let details = Details::new(game.clone(), self.internal.contains(game), self.internal.is_installed(game))
+            .with_usage(install::get_game_size(game), self.internal.get_usage())
+            .with_copies(copies);
+        match game.get_bundle() {
+            Some(bundle) => details.with_artwork(bundle.read_banner(), bundle.read_screenshots(MAX_SCREENSHOTS)),
+            None => details,
+        }
+    }
+
+    /// Opens the details screen for the selected game over the library.
+    fn open_details(&mut self) {
+        if self.state != State::Loading {
+            return;
+        }
+        if let Some(game) = self.library.get(self.count) {
+            self.details = Some(self.describe(game));
+        }
+    }
+
+    /// Performs the `choice` on the game shown on the details screen.
+    /// 
+    /// Games are installed and uninstalled on a separate thread, which reports
+    /// back with [Message::LibraryChanged].
+    fn choose_detail(&mut self, choice: Choice) -> Command<Message> {
+        let game = match &self.details {
+            Some(details) if details.is_busy() == false => details.get_game().clone(),
+            _ => return Command::none(),
+        };
+        match choice {
+            Choice::Play => {
+                self.details = None;
+                if let Some(i) = self.library.iter().position(|g| g == &game) {
+                    self.count = i;
+                }
+                Self::report(self.select_game());
+                Command::none()
+            }
+            Choice::PlayCopy(i) => {
+                let copy = self.details.take().and_then(|d| d.get_copies().get(i).cloned());
+                if let Some(copy) = copy {
+                    Self::report(self.start_game(copy));
+                }
+                Command::none()
+            }
+            Choice::Install | Choice::Uninstall => {
+                if let Some(details) = &mut self.details {
+                    details.set_busy(true);
+                }
+                let internal = self.internal.clone();
+                Command::perform(
+                    background(move || {
+                        let (result, key) = match choice {
+                            Choice::Install => {
+                                log::info!("Installing {:?} to {:?} ...", game.get_name(), internal.get_dir());
+                                (internal.install(&game), "game-installed")
+                            }
+                            _ => {
+                                log::info!("Uninstalling {:?} ...", game.get_name());
+                                (internal.uninstall(&game), "game-uninstalled")
+                            }
+                        };
+                        match result {
+                            Ok(()) => {
+                                notify::raise(Notice::info(key).with("name", game.get_name()));
+                                true
+                            }
+                            Err(e) => {
+                                log::error!("{}", e);
+                                notify::raise(e.to_notice());
+                                false
+                            }
+                        }
+                    }),
+                    |changed| Message::LibraryChanged(changed.unwrap_or(false)),
+                )
+            }
+            Choice::Back => {
+                self.details = None;
+                Command::none()
+            }
+        }
+    }
+
+    /// Reloads the installed games after a game is installed or uninstalled,
+    /// closing the details screen if its game is no longer in the library.
+    fn refresh_library(&mut self) {
+        let id = self.internal.get_id();
+        Self::report(self.sources.load(&id).map(|_| ()));
+        // the internal library's directory is created by its first install
+        self.watch_sources();
+        self.merge_library();
+        if self.state == State::Loading {
+            self.show_library();
+        }
     }
 
     /// Checks if the gamestick is available on the filesystem and changes the
@@ -203,7 +945,7 @@ impl Os {
     /// - Gamestick filesytem does not exist: LED = `off`
     #[cfg(feature = "rpi")]
     fn update_gamestick_led(&mut self) {
-        match self.drive.exists() {
+        match self.sources.has_removable() {
             true => self.io.enable_gsk_led(),
             false => self.io.disable_gsk_led(),
         }
@@ -221,20 +963,167 @@ impl Os {
     }
 
     /// Reads the stored data for the input buttons to call certain functionality.
+    /// 
+    /// Returns the commands of the actions triggered by the buttons.
     #[cfg(feature = "rpi")]
-    fn update_inputs(&mut self) {
+    fn update_inputs(&mut self) -> Command<Message> {
+        let mut commands = Vec::new();
         // remove the drive from the filesystem
         if self.io.check_eject_triggered() == true {
-            let _ = self.remove_drive();
+            commands.push(self.handle_action(Action::Eject));
         }
         // return to the home screen (quit Godot process)
         if self.io.check_home_triggered() == true {
-            let _ = self.quit_game();
+            commands.push(self.handle_action(Action::Home));
         }
         // send the system in sleep state
         if self.io.check_power_triggered() == true {
-            let _ = self.power_down();
+            Self::report(self.power_down());
         }
+        Command::batch(commands)
+    }
+
+    /// Changes the value of the setting highlighted on the settings menu to its
+    /// next value, or its previous value when `forward` is `false`.
+    fn change_setting(&mut self, forward: bool) {
+        let setting = match &self.menu {
+            Some(m) => m.get_selected(),
+            None => return,
+        };
+        self.settings_changed = true;
+        match setting {
+            Setting::Theme => {
+                self.themes.cycle(forward);
+                self.config.set("display", "theme", self.themes.get_current().get_name());
+                self.insert_animation = Animation::new(self.themes.get_current().get_insert_art());
+            }
+            Setting::Language => {
+                self.locales.cycle(forward);
+                self.config.set("display", "language", self.locales.get_current().get_code());
+            }
+            Setting::ReduceMotion => {
+                self.motion.set_reduced(!self.motion.is_reduced());
+                self.config.set("display", "reduce-motion", &self.motion.is_reduced().to_string());
+            }
+        }
+    }
+
+    /// Displays the value of the setting `setting`.
+    fn get_setting_value(&self, setting: Setting) -> String {
+        match setting {
+            Setting::Theme => self.themes.get_current().get_name().to_string(),
+            Setting::Language => self.locales.get_current().get_name().to_string(),
+            Setting::ReduceMotion => match self.motion.is_reduced() {
+                true => self.locales.get("on").to_string(),
+                false => self.locales.get("off").to_string(),
+            },
+        }
+    }
+
+    /// Determines the font for drawing text.
+    /// 
+    /// The current language's font takes priority over the theme's font so that
+    /// scripts missing from the theme's font can still be drawn.
+    fn get_font(&self) -> Font {
+        self.locales
+            .get_current()
+            .get_font()
+            .unwrap_or(self.themes.get_current().get_font())
+    }
+
+    /// Checks if the settings menu, details screen or diagnostics screen is
+    /// open over the current screen.
+    fn has_overlay(&self) -> bool {
+        self.menu.is_some() == true || self.details.is_some() == true || self.diagnostics.is_some() == true
+    }
+
+    /// Closes the settings menu and saves the changed settings to the
+    /// `[display]` table of the configuration file, if any were changed.
+    fn close_menu(&mut self) {
+        self.menu = None;
+        if self.settings_changed == false {
+            return;
+        }
+        self.settings_changed = false;
+        if let Err(e) = self.config.save_section(&Config::default_path(), "display") {
+            log::error!("Failed to save settings: {}", e);
+            notify::raise(Notice::error("settings-save-failed").with("reason", e));
+        }
+    }
+
+    /// Performs the console's response to the user triggering `action` in the
+    /// current state.
+    fn handle_action(&mut self, action: Action) -> Command<Message> {
+        // only returning home is handled while a game is launching or running
+        if self.is_playing() == true && action != Action::Home {
+            return Command::none();
+        }
+        // the settings menu captures every action while it is open
+        if let Some(menu) = &mut self.menu {
+            match action {
+                Action::Up => menu.move_up(),
+                Action::Down => menu.move_down(),
+                Action::Left => self.change_setting(false),
+                Action::Right | Action::Confirm => self.change_setting(true),
+                Action::Back | Action::Menu | Action::Home => self.close_menu(),
+                Action::Eject | Action::Refresh => (),
+            }
+            return Command::none();
+        }
+        // the details screen captures every action while it is open
+        if let Some(details) = &mut self.details {
+            match action {
+                Action::Up => details.move_up(),
+                Action::Down => details.move_down(),
+                Action::Confirm => {
+                    let choice = details.get_selected();
+                    return self.choose_detail(choice);
+                }
+                Action::Back | Action::Home => self.details = None,
+                Action::Left | Action::Right | Action::Menu | Action::Eject | Action::Refresh => (),
+            }
+            return Command::none();
+        }
+        // the diagnostics screen captures every action while it is open
+        if let Some(first) = self.diagnostics {
+            let last = self.sources.get_report().len().saturating_sub(1);
+            match action {
+                Action::Up => self.diagnostics = Some(first.saturating_sub(1)),
+                Action::Down => self.diagnostics = Some((first + 1).min(last)),
+                Action::Back | Action::Confirm | Action::Home => self.diagnostics = None,
+                Action::Left | Action::Right | Action::Menu | Action::Eject | Action::Refresh => (),
+            }
+            return Command::none();
+        }
+        match action {
+            // open the settings menu over the library or insert screen
+            Action::Menu if self.state == State::Requesting || self.state == State::Loading => self.menu = Some(Menu::new()),
+            // return to the home screen from any state
+            Action::Home => self.quit_game(),
+            // clear the notifications off the screen
+            Action::Back if self.toasts.is_empty() == false => self.toasts.dismiss(),
+            // read the library's sources again from the library or insert screen
+            Action::Refresh if self.state == State::Requesting || self.state == State::Loading => return self.refresh_all(),
+            // list the files left out of the library from the library or insert screen
+            Action::Down if self.state == State::Requesting || self.state == State::Loading => self.diagnostics = Some(0),
+            _ => match self.state {
+                // library input is ignored while a game is being played
+                State::Booting | State::Requesting | State::Copying(_) | State::Launching(..) | State::InGame(_) | State::Paused(..) => (),
+                State::Removed(_) => match action {
+                    Action::Confirm | Action::Back => self.show_library(),
+                    _ => (),
+                },
+                State::Loading => match action {
+                    Action::Right => { self.shift_shelf_right(); },
+                    Action::Left => { self.shift_shelf_left(); },
+                    Action::Up => self.open_details(),
+                    Action::Confirm => Self::report(self.select_game()),
+                    Action::Eject => Self::report(self.remove_drive()),
+                    _ => (),
+                },
+            },
+        }
+        Command::none()
     }
 }
 
@@ -244,91 +1133,166 @@ pub enum Message {
     EventOccurred(Event),
     ScanDrive(Instant),
     PlayGame,
+    SelectSlot(usize),
     UpdateIo(Instant),
+    PollGamepad,
+    NextFrame(Instant),
+    Tween(Instant),
+    LibraryLoaded(Option<Library>),
+    UpdateToasts(Instant),
+    CheckGame(Instant),
+    OpenDetails,
+    ChooseDetail(Choice),
+    LibraryChanged(bool),
+    RefreshLibrary,
+    LibraryRescanned(Vec<(String, Option<(Vec<Game>, Report)>)>),
+    OpenDiagnostics,
 }
 
 impl Application for Os {
     type Message = Message;
     type Executor = executor::Default;
     type Theme = Theme;
-    type Flags = ();
+    type Flags = Config;
+
+    fn theme(&self) -> Theme {
+        self.themes.get_current().to_theme()
+    }
 
-    fn new(_flags: ()) -> (Os, Command<Message>) {
+    fn new(flags: Config) -> (Os, Command<Message>) {
+        let os = Os::new(&flags);
+        let boot = os.start_boot();
         (
-            Os::new(),
-            // determine at run-time the full-screen mode
-            match std::env::var(crate::env::GOCO_NO_FULLSCREEN) {
-                Ok(_) => Command::none(),
-                Err(_) => iced::window::change_mode::<Message>(window::Mode::Fullscreen),
-            },
+            os,
+            Command::batch(vec![
+                // determine at run-time the full-screen mode
+                match std::env::var(crate::env::GOCO_NO_FULLSCREEN) {
+                    Ok(_) => Command::none(),
+                    Err(_) => iced::window::change_mode::<Message>(window::Mode::Fullscreen),
+                },
+                boot,
+            ]),
         )
     }
 
     fn title(&self) -> String {
-        String::from("GOCO")
+        String::from(WINDOW_TITLE)
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
-        match message {
+        let was_playing = self.is_playing();
+        let command = match message {
             // handle background checking the filesystem for the gamestick directory
-            Message::ScanDrive(_instant) => {
+            Message::ScanDrive(instant) => {
+                let mut command = Command::none();
                 match self.state {
-                    State::Requesting => {
-                        // attempt to load the gamestick's library
-                        if self.drive.exists() == true { 
-                            println!("info: GAMESTICK detected ...");
-                            self.initialize_library();
-                        } else {
-                            println!("info: Scanning for GAMESTICK at directory: {:?}", self.drive.get_path());
-                            self.insert_animation.next();
+                    // the sources are being read in the background
+                    State::Booting => (),
+                    State::Requesting | State::Loading => {
+                        // remove the games of missing sources
+                        self.refresh_sources();
+                        if self.state == State::Requesting {
+                            throttled!(SCAN_LOG_PERIOD, log::Level::Debug, "Scanning {} library source(s) for games ...", self.sources.count_sources());
+                        }
+                        // read newly inserted sources and the sources whose files changed since the last scan
+                        if self.rescanning == false {
+                            let mut ids = watch::drain();
+                            ids.extend(self.sources.get_arrivals());
+                            command = self.rescan_sources(ids);
+                        }
+                    }
+                    // stop the game before it reads from its removed pack
+                    State::Copying(_) | State::Launching(..) | State::InGame(_) => {
+                        if self.is_source_missing() == true {
+                            Self::report(self.handle_removal(instant));
                         }
                     }
-                    State::Loading => {
-                        // attempt to remove the gamestick's library (USB media is gone)
-                        if self.drive.exists() == false {
-                            println!("info: Removing GAMESTICK ...");
-                            self.flush_library();
+                    State::Paused(..) => Self::report(self.check_paused(instant)),
+                    State::Removed(since) => {
+                        if self.sources.has_arrivals() == true || instant.saturating_duration_since(since) > REMOVED_SCREEN_TIME {
+                            self.show_library();
                         }
                     }
                 }
-                Command::none()
+                command
             }
             // handle event to enter a game
             Message::PlayGame => {
-                self.select_game();
+                if self.has_overlay() == false {
+                    Self::report(self.select_game());
+                }
                 Command::none()
             }
-            // handle keyboard input (only subscribed during `Loading` state)
+            // handle clicking or tapping a game on the shelf
+            Message::SelectSlot(slot) => {
+                // the end of a drag across the shelf is not a tap on a game
+                if self.pointer.is_dragging() == false && self.has_overlay() == false {
+                    match slot {
+                        0 => { self.shift_shelf_left(); },
+                        1 => Self::report(self.select_game()),
+                        _ => { self.shift_shelf_right(); },
+                    }
+                }
+                Command::none()
+            }
+            // handle keyboard input by translating keys into console actions
             Message::EventOccurred(Event::Keyboard(event)) => {
-                match self.state {
-                    State::Loading => {
-                        if let KeyPressed { key_code, modifiers: _ } = event {
-                            match key_code {
-                                // right
-                                KeyCode::D => { self.shift_shelf_right(); },
-                                // left
-                                KeyCode::A => { self.shift_shelf_left(); },
-                                // down
-                                KeyCode::S => { },
-                                // up
-                                KeyCode::W => { },
-                                // action key (spacebar)
-                                KeyCode::Space => { self.select_game(); }
-                                // allow the user to eject the drive using the 'E' key
-                                #[cfg(not(feature = "rpi"))]
-                                KeyCode::E => { self.remove_drive(); }
-                                _ => (),
-                            }
-                        }
+                let mut commands = Vec::new();
+                if let KeyPressed { key_code, modifiers: _ } = event {
+                    for action in self.bindings.get_actions(key_code) {
+                        commands.push(self.handle_action(action));
+                    }
+                }
+                Command::batch(commands)
+            }
+            // handle dragging the shelf with the mouse
+            Message::EventOccurred(Event::Mouse(event)) => {
+                match event {
+                    mouse::Event::CursorMoved { position } => {
+                        let steps = self.pointer.moved(position);
+                        self.scroll_shelf(steps);
+                    }
+                    mouse::Event::ButtonPressed(mouse::Button::Left) => self.pointer.press_here(),
+                    mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                        let steps = self.pointer.release_here();
+                        self.scroll_shelf(steps);
                     }
                     _ => (),
                 }
                 Command::none()
             }
+            // handle swiping the shelf on a touchscreen
+            Message::EventOccurred(Event::Touch(event)) => {
+                match event {
+                    touch::Event::FingerPressed { id: _, position } => self.pointer.press(position),
+                    touch::Event::FingerMoved { id: _, position } => {
+                        let steps = self.pointer.moved(position);
+                        self.scroll_shelf(steps);
+                    }
+                    touch::Event::FingerLifted { id: _, position } | touch::Event::FingerLost { id: _, position } => {
+                        let steps = self.pointer.release(position);
+                        self.scroll_shelf(steps);
+                    }
+                }
+                Command::none()
+            }
+            // handle fitting the layout to the window
+            Message::EventOccurred(Event::Window(window::Event::Resized { width, height })) => {
+                self.metrics.resize(width, height);
+                Command::none()
+            }
             // handle window closing
             Message::EventOccurred(Event::Window(window::Event::CloseRequested)) => {
+                if let Some(mut child) = self.cursor_hider.take() {
+                    let _ = child.kill();
+                }
                 window::close()
             }
+            // handle the engine's window taking focus from the console
+            Message::EventOccurred(Event::Window(window::Event::Unfocused)) => {
+                self.enter_game();
+                Command::none()
+            }
             Message::EventOccurred(_) => {
                 Command::none()
             }
@@ -336,85 +1300,697 @@ impl Application for Os {
             Message::UpdateIo(_) => {
                 #[cfg(feature = "rpi")]
                 {
-                    println!("info: Refreshing IO ...");
+                    throttled!(SCAN_LOG_PERIOD, log::Level::Trace, "Refreshing IO ...");
                     self.update_gamestick_led();
                     self.update_power_led();
-                    self.update_inputs();
+                    self.update_inputs()
+                }
+                #[cfg(not(feature = "rpi"))]
+                {
+                    Command::none()
+                }
+            }
+            // handle actions triggered by the gamepads
+            Message::PollGamepad => {
+                // the game owns the gamepads while it runs, apart from the chord to return home
+                let playing = self.is_playing();
+                let commands: Vec<Command<Message>> = gamepad::drain()
+                    .into_iter()
+                    .filter(|press| playing == false || press.is_chord() == true)
+                    .map(|press| self.handle_action(press.get_action()))
+                    .collect();
+                Command::batch(commands)
+            }
+            Message::NextFrame(instant) => {
+                match self.state {
+                    State::Booting => self.boot_animation.tick(instant),
+                    _ => self.insert_animation.tick(instant),
+                }
+                Command::none()
+            }
+            Message::Tween(instant) => {
+                self.motion.tick(instant);
+                Command::none()
+            }
+            Message::LibraryLoaded(sources) => {
+                self.finish_boot(sources);
+                Command::none()
+            }
+            // show the notifications raised throughout the console
+            Message::UpdateToasts(instant) => {
+                for notice in notify::drain() {
+                    self.toasts.push(notice);
+                }
+                self.toasts.tick(instant);
+                Command::none()
+            }
+            // handle the game starting or exiting on the engine
+            Message::CheckGame(instant) => {
+                Self::report(self.check_game(instant));
+                // the console's window is brought back again once the quit game's window is gone
+                match self.engine.poll_stopped() {
+                    Some(result) => {
+                        Self::report(result);
+                        match self.is_playing() {
+                            true => Command::none(),
+                            false => self.restore_window(),
+                        }
+                    }
+                    None => Command::none(),
+                }
+            }
+            // handle clicking or tapping the details button
+            Message::OpenDetails => {
+                self.open_details();
+                Command::none()
+            }
+            Message::OpenDiagnostics => {
+                if self.state == State::Requesting || self.state == State::Loading {
+                    self.diagnostics = Some(0);
+                }
+                Command::none()
+            }
+            // handle clicking or tapping a choice on the details screen
+            Message::ChooseDetail(choice) => self.choose_detail(choice),
+            // handle a game finishing being installed or uninstalled
+            Message::LibraryChanged(changed) => {
+                if let Some(details) = &mut self.details {
+                    details.set_busy(false);
+                }
+                if changed == true {
+                    self.refresh_library();
                 }
                 Command::none()
             }
+            // handle clicking or tapping the refresh button
+            Message::RefreshLibrary => self.refresh_all(),
+            // handle the changed sources finishing being read again
+            Message::LibraryRescanned(games) => {
+                self.finish_rescan(games);
+                Command::none()
+            }
+        };
+        // the engine's window may have left the console behind the desktop
+        match was_playing == true && self.is_playing() == false {
+            true => Command::batch(vec![command, self.restore_window()]),
+            false => command,
         }
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        Subscription::batch(vec![
+        let mut subscriptions = vec![
             subscription::events().map(Message::EventOccurred),
             time::every(Duration::from_millis(500)).map(Message::UpdateIo),
+            time::every(Duration::from_millis(50)).map(|_| Message::PollGamepad),
             time::every(Duration::from_millis(1000)).map(Message::ScanDrive),
-        ])
+            time::every(Duration::from_millis(250)).map(Message::UpdateToasts),
+        ];
+        // only redraw the boot and insert animations while they are on screen
+        match self.state {
+            State::Booting => subscriptions.push(time::every(self.boot_animation.get_tick_rate()).map(Message::NextFrame)),
+            State::Requesting => subscriptions.push(time::every(self.insert_animation.get_tick_rate()).map(Message::NextFrame)),
+            State::Loading => (),
+            // check on the engine often enough to turn the spinner
+            State::Copying(_) | State::Launching(..) => subscriptions.push(time::every(SPINNER_STEP).map(Message::CheckGame)),
+            State::InGame(_) => subscriptions.push(time::every(Duration::from_millis(500)).map(Message::CheckGame)),
+            State::Paused(..) | State::Removed(_) => (),
+        }
+        // check on the game being quit until its process is gone
+        if self.engine.is_stopping() == true {
+            subscriptions.push(time::every(SPINNER_STEP).map(Message::CheckGame));
+        }
+        // only step the shelf's transitions while they are running
+        if self.state == State::Loading && self.motion.is_animating() == true {
+            subscriptions.push(time::every(TWEEN_TICK).map(Message::Tween));
+        }
+        Subscription::batch(subscriptions)
     }
 
     fn view(&self) -> Element<Message> {
-        match self.state {
+        let font = self.get_font();
+        let screen: Element<Message> = match &self.state {
+            State::Booting => {
+                container(self.boot_animation.draw())
+                .padding(self.metrics.scale(128.0))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y()
+                .into()
+            },
             State::Requesting => {
-                iced::widget::column![
-                    text(self.insert_animation.get_text())
+                let mut column = iced::widget::column![
+                    text(self.insert_animation.get_text(self.locales.get("insert-gamestick")))
+                    .size(self.metrics.scale(50.0))
+                    .font(font)
                     .vertical_alignment(iced::alignment::Vertical::Center)
                     .horizontal_alignment(iced::alignment::Horizontal::Center),
                     self.insert_animation.draw()
-                ]
-                .padding(128)
+                ];
+                // a drive whose games were all skipped looks like no drive at all
+                let skipped = self.sources.get_report().count_skipped();
+                if skipped > 0 {
+                    column = column.push(
+                        text(self.locales.format("diagnostics-hint", &[("count", &skipped.to_string())]))
+                        .size(self.metrics.scale(24.0))
+                        .font(font)
+                        .horizontal_alignment(iced::alignment::Horizontal::Center)
+                    );
+                }
+                column
+                .padding(self.metrics.scale(128.0))
                 .width(Length::Fill)
                 .height(Length::Fill)
-                .spacing(20)
+                .spacing(self.metrics.scale(20.0))
                 .align_items(Alignment::Center)
                 .into()
             },
             State::Loading => {
                 let nearby_games = self.get_nearby_games();
+                // slide the shelf from where the games were before moving; the row
+                // is centered, so padding one side moves it by half the padding
+                let slot_width = Game::icon_size(1.0, &self.metrics) as f32
+                    + self.metrics.scale(64.0 + 24.0) as f32;
+                let offset = (2.0 * self.motion.get_slide() * slot_width).round();
+                let shelf_padding = match offset > 0.0 {
+                    true => [0, 0, 0, offset as u16],
+                    false => [0, -offset as u16, 0, 0],
+                };
                 // use a column: a simple vertical layout
                 iced::widget::column![
                     // display the game's in a row      
                     iced::widget::row![
                         // game appear on the LHS
-                        match nearby_games[0] { Some(g) => { Container::new(g.draw(false)) } None => { Container::new(Game::blank()) } },
+                        self.draw_slot(0, nearby_games[0]),
                         // the middle index (`1`) is the selected game
-                        match nearby_games[1] { Some(g) => { Container::new(g.draw(true)) } None => { Container::new(Game::blank()) } },
+                        self.draw_slot(1, nearby_games[1]),
                         // game appear on the RHS
-                        match nearby_games[2] { Some(g) => { Container::new(g.draw(false)) } None => { Container::new(Game::blank()) } },
+                        self.draw_slot(2, nearby_games[2]),
+                    ]
+                    .spacing(self.metrics.scale(64.0))
+                    .padding(shelf_padding)
+                    .align_items(Alignment::Center),
+                    iced::widget::row![
+                        button(text(self.locales.get("play")).size(self.metrics.scale(32.0)).font(font))
+                        .padding(self.metrics.scale(10.0))
+                        .on_press(Message::PlayGame),
+                        button(text(self.locales.get("details")).size(self.metrics.scale(32.0)).font(font))
+                        .padding(self.metrics.scale(10.0))
+                        .on_press(Message::OpenDetails),
+                        button(text(self.locales.get("refresh-library")).size(self.metrics.scale(32.0)).font(font))
+                        .padding(self.metrics.scale(10.0))
+                        .on_press(Message::RefreshLibrary),
+                        button(text(self.locales.get("diagnostics")).size(self.metrics.scale(32.0)).font(font))
+                        .padding(self.metrics.scale(10.0))
+                        .on_press(Message::OpenDiagnostics),
                     ]
-                    .spacing(64),
-                    button("PLAY").on_press(Message::PlayGame),
+                    .spacing(self.metrics.scale(32.0)),
                 ]
-                .padding(32)
+                .padding(self.metrics.scale(32.0))
                 .width(Length::Fill)
                 .height(Length::Fill)
-                .spacing(64)
+                .spacing(self.metrics.scale(64.0))
                 .align_items(Alignment::Center)
                 .into()
             },
+            State::Copying(game) => {
+                let title = self.themes.get_current().get_palette().text;
+                let progress = self.copy.as_ref().map_or(0.0, |job| job.get_progress());
+                iced::widget::column![
+                    game.draw(game::SELECTED_ZOOM, title, &self.metrics, font),
+                    progress_bar(0.0..=1.0, progress)
+                    .width(Length::Fixed(self.metrics.scale(480.0) as f32))
+                    .height(Length::Fixed(self.metrics.scale(16.0) as f32)),
+                    text(self.locales.format("copying", &[
+                        ("name", game.get_name()),
+                        ("percent", &((progress * 100.0) as u32).to_string()),
+                    ]))
+                    .size(self.metrics.scale(32.0))
+                    .font(font),
+                ]
+                .padding(self.metrics.scale(32.0))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .spacing(self.metrics.scale(48.0))
+                .align_items(Alignment::Center)
+                .into()
+            },
+            State::Launching(game, since) => {
+                let title = self.themes.get_current().get_palette().text;
+                iced::widget::column![
+                    game.draw(game::SELECTED_ZOOM, title, &self.metrics, font),
+                    self.draw_spinner(*since),
+                    text(self.locales.format("launching", &[("name", game.get_name())]))
+                    .size(self.metrics.scale(32.0))
+                    .font(font),
+                ]
+                .padding(self.metrics.scale(32.0))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .spacing(self.metrics.scale(48.0))
+                .align_items(Alignment::Center)
+                .into()
+            },
+            State::InGame(game) => {
+                let title = self.themes.get_current().get_palette().text;
+                iced::widget::column![
+                    text(self.locales.get("now-playing").to_string())
+                    .size(self.metrics.scale(50.0))
+                    .font(font),
+                    game.draw(1.0, title, &self.metrics, font),
+                    text(self.locales.get("quit-hint").to_string())
+                    .size(self.metrics.scale(24.0))
+                    .font(font),
+                ]
+                .padding(self.metrics.scale(32.0))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .spacing(self.metrics.scale(48.0))
+                .align_items(Alignment::Center)
+                .into()
+            },
+            State::Paused(game, since) => {
+                let left = self.removal_grace.saturating_sub(since.elapsed()).as_secs() + 1;
+                self.draw_removed(
+                    self.locales.format("stick-paused", &[("name", game.get_name())]),
+                    self.locales.format("stick-paused-hint", &[("seconds", &left.to_string())]),
+                )
+            },
+            State::Removed(_) => {
+                self.draw_removed(
+                    self.locales.get("stick-removed").to_string(),
+                    self.locales.get("stick-removed-hint").to_string(),
+                )
+            },
+        };
+
+        // draw the theme's background image behind the screen
+        let mut layers: Layers<Message, iced::Renderer> = match self.themes.get_current().get_background() {
+            Some(path) => Layers::new(
+                image(path.clone())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .content_fit(ContentFit::Cover)
+            ).push(screen),
+            None => Layers::new(screen),
+        };
+        // draw the details screen over the library
+        if let Some(details) = &self.details {
+            layers = layers.push_modal(self.draw_details(details));
+        }
+        // draw the diagnostics screen over the library or insert screen
+        if let Some(first) = self.diagnostics {
+            layers = layers.push_modal(self.draw_diagnostics(first));
+        }
+        // draw the settings menu over the screen
+        if let Some(menu) = &self.menu {
+            layers = layers.push_modal(self.draw_menu(menu));
+        }
+        // draw the notifications above everything else
+        if self.toasts.is_empty() == false {
+            layers = layers.push(self.draw_toasts());
+        }
+        layers.into()
+    }
+}
+
+impl Os {
+    /// Assembles a slot on the shelf that can be clicked or tapped. The middle
+    /// slot (`1`) holds the selected game, which is surrounded by the theme's
+    /// highlight.
+    fn draw_slot<'a>(&self, slot: usize, game: Option<&Game>) -> Element<'a, Message> {
+        let current = self.themes.get_current();
+        match game {
+            Some(g) => {
+                // grow the selected game's icon and fade in the titles
+                let zoom = match slot {
+                    1 => 1.0 + (game::SELECTED_ZOOM - 1.0) * self.motion.get_zoom(),
+                    _ => 1.0,
+                };
+                let title = Color {
+                    a: self.motion.get_fade(),
+                    ..current.get_palette().text
+                };
+                let content = container(
+                    g.draw(zoom, title, &self.metrics, self.get_font())
+                    .push(self.draw_badge(g))
+                )
+                .padding(self.metrics.scale(12.0));
+                let content = match slot {
+                    1 => content.style(theme::Container::Custom(Box::new(current.get_highlight()))),
+                    _ => content,
+                };
+                // a press on a game may also begin a drag across the shelf
+                Tap::new(content, Message::SelectSlot(slot)).into()
+            }
+            None => Container::new(Game::blank(&self.metrics))
+                .padding(self.metrics.scale(12.0))
+                .into(),
         }
+    }
 
+    /// Assembles a badge naming where the `game` is stored.
+    fn draw_badge<'a>(&self, game: &Game) -> Element<'a, Message> {
+        let current = self.themes.get_current();
+        let kind = self.sources.get_source(game.get_source()).map(|s| s.get_kind());
+        let (label, accent) = match kind {
+            Some(SourceKind::Internal) => ("source-internal", current.get_palette().success),
+            Some(SourceKind::Directory) => ("source-directory", current.get_palette().text),
+            Some(SourceKind::Stick) | None => ("source-stick", current.get_palette().primary),
+        };
+        container(
+            text(self.locales.get(label).to_string())
+            .size(self.metrics.scale(18.0))
+            .font(self.get_font())
+        )
+        .padding([self.metrics.scale(4.0), self.metrics.scale(12.0)])
+        .style(theme::Container::Custom(Box::new(current.get_panel(accent))))
+        .into()
+    }
+
+    /// Assembles the details screen showing the game, where it is stored, and
+    /// the space it takes beside a button for each choice. The highlighted
+    /// choice is surrounded by the theme's highlight.
+    fn draw_details<'a>(&self, details: &Details) -> Element<'a, Message> {
+        let current = self.themes.get_current();
+        let font = self.get_font();
+        let game = details.get_game();
+        let mut info = iced::widget::column![]
+            .spacing(self.metrics.scale(16.0))
+            .align_items(Alignment::Center);
+        // a bundle's banner is shown above the game, and its screenshots below
+        if let Some(banner) = details.get_banner() {
+            info = info.push(
+                image(banner.clone())
+                .width(Length::Fixed(self.metrics.scale(480.0) as f32))
+                .content_fit(ContentFit::Contain)
+            );
+        }
+        info = info
+            .push(game.draw(1.0, current.get_palette().text, &self.metrics, font))
+            .push(self.draw_badge(game))
+            .push(
+                text(self.locales.format("game-size", &[("size", &install::format_size(details.get_size()))]))
+                .size(self.metrics.scale(24.0))
+                .font(font)
+            )
+            .push(
+                text(self.locales.format("storage-used", &[("size", &install::format_size(details.get_usage()))]))
+                .size(self.metrics.scale(24.0))
+                .font(font)
+            );
+        if details.get_screenshots().is_empty() == false {
+            info = info.push(details.get_screenshots().iter().fold(
+                iced::widget::row![].spacing(self.metrics.scale(8.0)),
+                |row, screenshot| {
+                    row.push(
+                        image(screenshot.clone())
+                        .width(Length::Fixed(self.metrics.scale(144.0) as f32))
+                        .content_fit(ContentFit::Contain)
+                    )
+                },
+            ));
+        }
+        let choices: Element<'a, Message> = match details.is_busy() {
+            // the choices are hidden until the game is installed or uninstalled
+            true => text(self.locales.get("please-wait").to_string())
+                .size(self.metrics.scale(32.0))
+                .font(font)
+                .into(),
+            false => details.get_choices().into_iter().fold(
+                iced::widget::column![].spacing(self.metrics.scale(16.0)),
+                |column, choice| {
+                    let label = match choice {
+                        Choice::PlayCopy(i) => {
+                            let path = details.get_copies()[i].get_pck().display().to_string();
+                            self.locales.format(choice.get_label(), &[("path", &path)])
+                        }
+                        _ => self.locales.get(choice.get_label()).to_string(),
+                    };
+                    let entry = container(
+                        button(text(label).size(self.metrics.scale(32.0)).font(font))
+                        .on_press(Message::ChooseDetail(choice))
+                        .padding(0)
+                        .style(theme::Button::Text)
+                    )
+                    .padding(self.metrics.scale(12.0));
+                    column.push(match choice == details.get_selected() {
+                        true => entry.style(theme::Container::Custom(Box::new(current.get_highlight()))),
+                        false => entry,
+                    })
+                },
+            )
+            .into(),
+        };
+        container(
+            container(
+                iced::widget::row![info, choices]
+                .spacing(self.metrics.scale(64.0))
+                .align_items(Alignment::Center)
+            )
+            .padding(self.metrics.scale(32.0))
+            .style(theme::Container::Box)
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
+    }
+
+    /// Assembles the library diagnostics screen listing a page of the problems
+    /// found while reading the library, starting from the problem `first`.
+    fn draw_diagnostics<'a>(&self, first: usize) -> Element<'a, Message> {
+        const PAGE_SIZE: usize = 8;
+        let font = self.get_font();
+        let palette = self.themes.get_current().get_palette();
+        let report = self.sources.get_report();
+        let title = text(self.locales.get("library-diagnostics").to_string())
+            .size(self.metrics.scale(50.0))
+            .font(font);
+        let mut entries = iced::widget::column![title].spacing(self.metrics.scale(16.0));
+        if report.is_empty() == true {
+            entries = entries.push(
+                text(self.locales.get("no-problems").to_string())
+                .size(self.metrics.scale(32.0))
+                .font(font)
+            );
+        }
+        for finding in report.get_findings().iter().skip(first).take(PAGE_SIZE) {
+            let problem = finding.get_problem();
+            // files left out of the library stand out from the warnings
+            let color = match problem.is_skipped() {
+                true => palette.danger,
+                false => palette.text,
+            };
+            entries = entries.push(
+                iced::widget::column![
+                    iced::widget::row![
+                        text(self.locales.get(problem.get_label()).to_string())
+                        .size(self.metrics.scale(24.0))
+                        .font(font)
+                        .style(color),
+                        text(finding.get_path().display().to_string())
+                        .size(self.metrics.scale(24.0))
+                        .font(font),
+                    ]
+                    .spacing(self.metrics.scale(16.0)),
+                    text(finding.get_detail().to_string())
+                    .size(self.metrics.scale(18.0))
+                    .font(font),
+                ]
+                .spacing(self.metrics.scale(4.0))
+            );
+        }
+        if report.len() > PAGE_SIZE {
+            let last = (first + PAGE_SIZE).min(report.len());
+            entries = entries.push(
+                text(self.locales.format("diagnostics-page", &[
+                    ("first", &(first + 1).to_string()),
+                    ("last", &last.to_string()),
+                    ("count", &report.len().to_string()),
+                ]))
+                .size(self.metrics.scale(18.0))
+                .font(font)
+            );
+        }
+        container(
+            container(entries)
+            .padding(self.metrics.scale(32.0))
+            .style(theme::Container::Box)
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
+    }
+
+    /// Assembles the screen shown after the [GameStick] is removed during a game,
+    /// with a `message` in the theme's danger color above a `hint`.
+    fn draw_removed<'a>(&self, message: String, hint: String) -> Element<'a, Message> {
+        iced::widget::column![
+            text(message)
+            .size(self.metrics.scale(50.0))
+            .font(self.get_font())
+            .style(self.themes.get_current().get_palette().danger)
+            .horizontal_alignment(iced::alignment::Horizontal::Center),
+            text(hint)
+            .size(self.metrics.scale(32.0))
+            .font(self.get_font())
+            .horizontal_alignment(iced::alignment::Horizontal::Center),
+        ]
+        .padding(self.metrics.scale(128.0))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .spacing(self.metrics.scale(32.0))
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    /// Assembles a row of dots where the lit dot moves along the row for as long
+    /// as a game has been launching `since` the time it was selected. The dots
+    /// stay still when motion is reduced.
+    fn draw_spinner<'a>(&self, since: Instant) -> Element<'a, Message> {
+        let color = self.themes.get_current().get_palette().primary;
+        let size = self.metrics.scale(16.0);
+        let lit = match self.motion.is_reduced() {
+            true => 0,
+            false => (since.elapsed().as_millis() / SPINNER_STEP.as_millis()) as usize % SPINNER_DOTS,
+        };
+        (0..SPINNER_DOTS)
+            .fold(iced::widget::row![].spacing(self.metrics.scale(12.0)), |row, i| {
+                // the dots behind the lit dot fade out like a trail
+                let alpha = match (lit + SPINNER_DOTS - i) % SPINNER_DOTS {
+                    0 => 1.0,
+                    1 => 0.6,
+                    2 => 0.35,
+                    _ => 0.15,
+                };
+                row.push(
+                    container(iced::widget::Space::new(Length::Fixed(size as f32), Length::Fixed(size as f32)))
+                    .style(theme::Container::Custom(Box::new(Dot::new(Color { a: alpha, ..color }, size as f32 / 2.0))))
+                )
+            })
+            .into()
+    }
+
+    /// Assembles the notifications stacked in the bottom-right corner of the screen,
+    /// with the newest at the bottom. Each border is colored by its severity.
+    fn draw_toasts<'a>(&self) -> Element<'a, Message> {
+        let current = self.themes.get_current();
+        let toasts = self.toasts.iter().fold(
+            iced::widget::column![].spacing(self.metrics.scale(12.0)),
+            |column, notice| {
+                let accent = match notice.get_severity() {
+                    Severity::Info => current.get_palette().primary,
+                    Severity::Warning => Color::from_rgb8(0xf2, 0xa5, 0x3a),
+                    Severity::Error => current.get_palette().danger,
+                };
+                column.push(
+                    container(
+                        text(self.locales.format(notice.get_key(), &notice.get_args()))
+                        .size(self.metrics.scale(24.0))
+                        .font(self.get_font())
+                    )
+                    .padding(self.metrics.scale(16.0))
+                    .max_width(self.metrics.scale(640.0))
+                    .style(theme::Container::Custom(Box::new(current.get_panel(accent))))
+                )
+            },
+        );
+        container(toasts.align_items(Alignment::End))
+            .padding(self.metrics.scale(32.0))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Right)
+            .align_y(iced::alignment::Vertical::Bottom)
+            .into()
+    }
+
+    /// Assembles the settings menu listing each setting with its current value.
+    /// The highlighted setting is surrounded by the theme's highlight.
+    fn draw_menu<'a>(&self, menu: &Menu) -> Element<'a, Message> {
+        let current = self.themes.get_current();
+        let title = text(self.locales.get("settings").to_string())
+            .size(self.metrics.scale(50.0))
+            .font(self.get_font());
+        let entries = Setting::ALL.iter().fold(
+            iced::widget::column![title].spacing(self.metrics.scale(16.0)),
+            |column, setting| {
+                let entry = container(
+                    text(format!("{}:  < {} >", self.locales.get(setting.get_label()), self.get_setting_value(*setting)))
+                    .size(self.metrics.scale(32.0))
+                    .font(self.get_font())
+                )
+                .padding(self.metrics.scale(12.0));
+                column.push(match *setting == menu.get_selected() {
+                    true => entry.style(theme::Container::Custom(Box::new(current.get_highlight()))),
+                    false => entry,
+                })
+            },
+        );
+        container(
+            container(entries)
+            .padding(self.metrics.scale(32.0))
+            .style(theme::Container::Box)
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::path::PathBuf;
+    use crate::gamestick::GameStick;
+    use std::path::{Path, PathBuf};
 
-    #[test]
-    fn it_load_nearby_games() {
-        let mut os = Os {
-            drive: GameStick::new(),
-            insert_animation: Animation::new(),
-            state: State::Requesting,
-            engine: Engine::new(),
-            library: GameStick::load(&PathBuf::from(format!("{}/testenv/GAMESTICK", env!("CARGO_MANIFEST_DIR")))),
+    /// Creates an [Os] showing the games of the test GAMESTICK, which runs
+    /// its games on the fake `engine`.
+    fn test_os(engine: &str) -> Os {
+        let mut sources = Library::new();
+        sources.push(GameStick::test_new());
+        sources.refresh();
+        let library = sources.get_games();
+        Os {
+            sources: sources,
+            insert_animation: Animation::new(None),
+            state: State::Loading,
+            engine: Engine::test_new(Path::new(engine)),
+            library: library,
             count: 0,
+            bindings: Bindings::default(),
+            pointer: Pointer::new(),
+            cursor_hider: None,
+            metrics: Metrics::new(WINDOW_SIZE.0, WINDOW_SIZE.1, 1.0),
+            config: Config::new(),
+            themes: Themes::load(&PathBuf::from("testenv/themes"), None),
+            locales: Locales::load(&PathBuf::from("testenv/locales"), None),
+            motion: ShelfMotion::new(false),
+            boot_animation: Animation::new(None),
+            toasts: Toasts::new(),
+            menu: None,
+            settings_changed: false,
+            launch_timeout: Duration::from_secs(LAUNCH_TIMEOUT),
+            removal_grace: Duration::ZERO,
+            cache: None,
+            copy: None,
+            internal: InternalLibrary::new(&PathBuf::from("testenv/library")),
+            details: None,
+            rescanning: false,
+            diagnostics: None,
             #[cfg(feature = "rpi")]
             io: Io::new(),
-        };
+        }
+    }
+
+    #[test]
+    fn it_load_nearby_games() {
+        let mut os = test_os("godot");
         {
             let shelf = os.get_nearby_games();
             assert_eq!(shelf[0].is_none(), true);
@@ -443,4 +2019,101 @@ mod test {
             assert_eq!(shelf.iter().filter(|p| p.is_some()).count(), 2);
         }
     }
-}
\ No newline at end of file
+
+    /// Checks the game launched on the `os` until its state passes the `test`,
+    /// failing after a few seconds.
+    fn wait_for(os: &mut Os, test: impl Fn(&State) -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while test(&os.state) == false {
+            assert_eq!(Instant::now() < deadline, true, "stuck in {:?}", os.state);
+            os.check_game(Instant::now()).unwrap();
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn it_enter_and_leave_game() {
+        let mut os = test_os("testenv/engine/started.sh");
+        let game = os.library[1].clone();
+        os.launch_game(game.clone(), game.get_pck()).unwrap();
+        assert_eq!(matches!(os.state, State::Launching(..)), true);
+        // the game is entered once the engine prints its first line
+        wait_for(&mut os, |s| matches!(s, State::InGame(_)));
+        assert_eq!(os.state, State::InGame(game));
+        // the library is shown again with the game selected once it exits
+        wait_for(&mut os, |s| *s == State::Loading);
+        assert_eq!(os.count, 1);
+        assert_eq!(os.engine.is_in_game(), false);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn it_launch_timeout() {
+        let mut os = test_os("testenv/engine/silent.sh");
+        os.launch_timeout = Duration::from_secs(1);
+        let game = os.library[0].clone();
+        os.launch_game(game.clone(), game.get_pck()).unwrap();
+        let since = match os.state {
+            State::Launching(_, since) => since,
+            _ => panic!("unexpected state {:?}", os.state),
+        };
+        // the game keeps launching until the timeout passes
+        os.check_game(since).unwrap();
+        assert_eq!(matches!(os.state, State::Launching(..)), true);
+        // a quiet game is only quit if it can be told it never opened a window
+        let pid = os.engine.get_process_id().unwrap();
+        let result = os.check_game(since + Duration::from_secs(2));
+        match focus::has_window(pid) {
+            Some(false) => {
+                assert_eq!(matches!(result, Err(GocoError::LaunchTimeout(..))), true);
+                assert_eq!(os.state, State::Loading);
+            }
+            _ => {
+                assert_eq!(result.is_ok(), true);
+                assert_eq!(os.state, State::InGame(game));
+                os.quit_game();
+                assert_eq!(os.state, State::Loading);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn it_pause_removed_game() {
+        let dir = std::env::temp_dir().join(format!("goco-removal-{}", std::process::id()));
+        let moved = dir.with_extension("moved");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&moved);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("testenv/GAMESTICK/Pong.pck", dir.join("Pong.pck")).unwrap();
+        let mut os = test_os("testenv/engine/silent.sh");
+        os.sources = Library::new();
+        os.sources.push(crate::library::Directory::new(dir.clone()));
+        os.sources.refresh();
+        os.library = os.sources.get_games();
+        os.removal_grace = Duration::from_secs(5);
+
+        let game = os.library[0].clone();
+        os.launch_game(game.clone(), game.get_pck()).unwrap();
+        os.enter_game();
+        // the game is suspended while its drive is missing
+        std::fs::rename(&dir, &moved).unwrap();
+        let removed = Instant::now();
+        os.handle_removal(removed).unwrap();
+        assert_eq!(os.state, State::Paused(game.clone(), removed));
+        assert_eq!(os.engine.is_paused(), true);
+        // and continues once the drive returns in time
+        std::fs::rename(&moved, &dir).unwrap();
+        os.check_paused(removed + Duration::from_secs(1)).unwrap();
+        assert_eq!(os.state, State::InGame(game.clone()));
+        assert_eq!(os.engine.is_paused(), false);
+        // the game is quit once the drive stays away for too long
+        std::fs::rename(&dir, &moved).unwrap();
+        os.handle_removal(removed).unwrap();
+        os.check_paused(removed + Duration::from_secs(6)).unwrap();
+        assert_eq!(matches!(os.state, State::Removed(_)), true);
+        assert_eq!(os.engine.is_in_game(), false);
+        std::fs::remove_dir_all(&moved).unwrap();
+    }
+}
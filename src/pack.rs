@@ -0,0 +1,349 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::cache::Fnv;
+
+/// The magic bytes found at the start of every Godot pack file, and at the end
+/// of every executable with an embedded pack.
+pub const PCK_MAGIC: &[u8; 4] = b"GDPC";
+
+/// The file extension for a Godot main pack exported as a zip archive.
+pub const ZIP_EXT: &str = "zip";
+
+/// The file extensions of the executables Godot exports for Linux. A
+/// self-contained executable may also have no extension at all.
+pub const EXECUTABLE_EXTS: &[&str] = &["x86_64", "x86_32", "arm64", "arm32", "rv64", "64", "32"];
+
+/// The project settings stored in every main pack.
+const PROJECT_FILE: &str = "project.binary";
+
+/// The directory only Godot 4 projects store imported files in.
+const GODOT_4_DIR: &str = ".godot/";
+
+/// The size of the footer following a pack embedded in an executable: the
+/// pack's size followed by the magic bytes (in bytes).
+const FOOTER_SIZE: i64 = 8 + 4;
+
+/// How a game's pack is stored, which decides how the game is launched.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PackKind {
+    /// A .pck file, run by the engine as its main pack. The pack inside a
+    /// bundle is a .pck file too.
+    Pck,
+    /// A zip archive, run by the engine as its main pack.
+    Zip,
+    /// An executable exported with its pack embedded at the end, which is run
+    /// on its own.
+    Embedded,
+}
+
+impl PackKind {
+    /// Finds how the pack at `path` is stored from its name. A file that is not
+    /// named like a pack is a self-contained executable.
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ZIP_EXT) => Self::Zip,
+            Some(crate::game::GAME_EXT) | Some(crate::bundle::BUNDLE_EXT) => Self::Pck,
+            _ => Self::Embedded,
+        }
+    }
+}
+
+/// The flag set on Godot 4 packs whose file directory is encrypted.
+const PACK_DIR_ENCRYPTED: u32 = 1;
+
+/// The major versions of Godot whose packs the console can run.
+const SUPPORTED_ENGINES: std::ops::RangeInclusive<u32> = 3..=4;
+
+/// The longest file path accepted in a pack's file directory (in bytes).
+const MAX_PATH_LENGTH: u32 = 4096;
+
+/// The metadata stored at the beginning of a Godot .pck file.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PckHeader {
+    kind: PackKind,
+    /// Version of the pack file layout (`1` for Godot 3, `2` for Godot 4), or
+    /// `0` for a zip pack.
+    format: u32,
+    /// Version of the Godot engine that exported the pack as (major, minor, patch).
+    /// Only the major version is known for a zip pack.
+    engine: (u32, u32, u32),
+    /// Number of resource files stored in the pack.
+    file_count: u32,
+    /// Hash of the path, size and MD5 checksum of every file in the pack, so
+    /// packs exported from the same project files have the same fingerprint.
+    /// `None` if the pack's file directory cannot be read, such as when it is
+    /// encrypted.
+    fingerprint: Option<u64>,
+}
+
+impl PckHeader {
+    /// Reads the header from the start of a pack.
+    pub fn read<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != PCK_MAGIC {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "missing GDPC magic bytes",
+            ));
+        }
+        let format = Self::read_u32(reader)?;
+        let engine = (
+            Self::read_u32(reader)?,
+            Self::read_u32(reader)?,
+            Self::read_u32(reader)?,
+        );
+        // godot 4 packs store flags and the file base offset before the reserved space
+        let mut flags = 0;
+        if format >= 2 {
+            flags = Self::read_u32(reader)?;
+            let mut skip = [0u8; 8];
+            reader.read_exact(&mut skip)?;
+        }
+        // skip the reserved space
+        let mut reserved = [0u8; 16 * 4];
+        reader.read_exact(&mut reserved)?;
+        let file_count = Self::read_u32(reader)?;
+
+        Ok(Self {
+            kind: PackKind::Pck,
+            format: format,
+            engine: engine,
+            file_count: file_count,
+            // a directory that cannot be parsed leaves the pack without a fingerprint
+            fingerprint: match format <= 2 && flags & PACK_DIR_ENCRYPTED == 0 {
+                true => Self::hash_directory(reader, format, file_count).ok(),
+                false => None,
+            },
+        })
+    }
+
+    /// Reads the header from the pack file found at `path`, which may also be
+    /// a zip pack or a self-contained executable.
+    pub fn from_path(path: &Path) -> std::io::Result<Self> {
+        match PackKind::of(path) {
+            PackKind::Pck => Self::read(&mut BufReader::new(File::open(path)?)),
+            PackKind::Zip => Self::read_zip(path),
+            PackKind::Embedded => Self::read_embedded(path),
+        }
+    }
+
+    /// Reads the header of the pack embedded at the end of the executable at
+    /// `path`, which is found from the footer after it.
+    fn read_embedded(path: &Path) -> std::io::Result<Self> {
+        let mut file = File::open(path)?;
+        let length = file.seek(SeekFrom::End(0))?;
+        let size = match read_footer(&mut file)? {
+            Some(size) if size <= length - FOOTER_SIZE as u64 => size,
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "no pack is embedded in the executable",
+                ))
+            }
+        };
+        file.seek(SeekFrom::Start(length - FOOTER_SIZE as u64 - size))?;
+        let mut header = Self::read(&mut BufReader::new(file))?;
+        header.kind = PackKind::Embedded;
+        Ok(header)
+    }
+
+    /// Reads the list of files in the zip pack at `path`. The pack's
+    /// fingerprint hashes the path, size and checksum of every file.
+    fn read_zip(path: &Path) -> std::io::Result<Self> {
+        let mut archive = ZipArchive::new(BufReader::new(File::open(path)?)).map_err(to_io_error)?;
+        let mut files = Vec::new();
+        for i in 0..archive.len() {
+            let file = archive.by_index_raw(i).map_err(to_io_error)?;
+            if file.is_dir() == false {
+                files.push((file.name().to_string(), file.size(), file.crc32()));
+            }
+        }
+        if files.iter().any(|(name, _, _)| name == PROJECT_FILE) == false {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("the zip is not a Godot main pack (it has no {})", PROJECT_FILE),
+            ));
+        }
+        // files are hashed in order, wherever they are stored in the archive
+        files.sort();
+        let mut hash = Fnv::new();
+        for (name, size, crc) in &files {
+            hash.write(name.as_bytes());
+            hash.write(&size.to_le_bytes());
+            hash.write(&crc.to_le_bytes());
+        }
+        let major = match files.iter().any(|(name, _, _)| name.starts_with(GODOT_4_DIR)) {
+            true => 4,
+            false => 3,
+        };
+        Ok(Self {
+            kind: PackKind::Zip,
+            format: 0,
+            engine: (major, 0, 0),
+            file_count: files.len() as u32,
+            fingerprint: Some(hash.finish()),
+        })
+    }
+
+    /// Hashes the file directory following the header, which lists the path,
+    /// offset, size and MD5 checksum of each of the `file_count` files.
+    fn hash_directory<R: Read>(reader: &mut R, format: u32, file_count: u32) -> std::io::Result<u64> {
+        let mut hash = Fnv::new();
+        for _ in 0..file_count {
+            let length = Self::read_u32(reader)?;
+            if length > MAX_PATH_LENGTH {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "a file path in the pack's directory is too long",
+                ));
+            }
+            let mut path = vec![0u8; length as usize];
+            reader.read_exact(&mut path)?;
+            let mut entry = [0u8; 8 + 8 + 16];
+            reader.read_exact(&mut entry)?;
+            // the offset moves with the files before it, so it is left out
+            hash.write(&path);
+            hash.write(&entry[8..]);
+            // godot 4 packs store flags after each file's checksum
+            if format >= 2 {
+                Self::read_u32(reader)?;
+            }
+        }
+        Ok(hash.finish())
+    }
+
+    fn read_u32<R: Read>(reader: &mut R) -> std::io::Result<u32> {
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    /// Accesses the pack's file layout version.
+    #[cfg(test)]
+    pub fn get_format(&self) -> u32 {
+        self.format
+    }
+
+    /// Accesses the version of the engine that exported the pack.
+    pub fn get_engine_version(&self) -> (u32, u32, u32) {
+        self.engine
+    }
+
+    /// Accesses the number of resource files stored in the pack.
+    #[cfg(test)]
+    pub fn get_file_count(&self) -> u32 {
+        self.file_count
+    }
+
+    /// Checks if the pack was exported by a version of Godot the console can run.
+    pub fn is_supported(&self) -> bool {
+        SUPPORTED_ENGINES.contains(&self.engine.0)
+    }
+
+    /// Accesses the hash of the pack's file directory, if it could be read.
+    pub fn get_fingerprint(&self) -> Option<u64> {
+        self.fingerprint
+    }
+}
+
+impl std::fmt::Display for PckHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            PackKind::Pck => write!(f, "Godot {}.{}.{} (pack format {}, {} files)", self.engine.0, self.engine.1, self.engine.2, self.format, self.file_count),
+            PackKind::Zip => write!(f, "Godot {}.x (zip pack, {} files)", self.engine.0, self.file_count),
+            PackKind::Embedded => write!(f, "Godot {}.{}.{} (pack format {}, {} files, embedded in an executable)", self.engine.0, self.engine.1, self.engine.2, self.format, self.file_count),
+        }
+    }
+}
+
+/// Checks if the file at `path` ends with the footer of an embedded pack,
+/// which marks a self-contained executable.
+pub fn has_embedded_pack(path: &Path) -> bool {
+    match File::open(path) {
+        Ok(mut file) => read_footer(&mut file).ok().flatten().is_some(),
+        Err(_) => false,
+    }
+}
+
+/// Reads the size of the pack embedded before the footer at the end of the
+/// `file`, or `None` if the file does not end with a footer.
+fn read_footer(file: &mut File) -> std::io::Result<Option<u64>> {
+    if file.seek(SeekFrom::End(0))? < FOOTER_SIZE as u64 {
+        return Ok(None);
+    }
+    file.seek(SeekFrom::End(-FOOTER_SIZE))?;
+    let mut footer = [0u8; FOOTER_SIZE as usize];
+    file.read_exact(&mut footer)?;
+    match &footer[8..] == PCK_MAGIC {
+        true => Ok(Some(u64::from_le_bytes(footer[..8].try_into().unwrap()))),
+        false => Ok(None),
+    }
+}
+
+/// Converts an error reading a zip archive into an I/O error, where an
+/// archive that cannot be read is invalid data.
+pub fn to_io_error(e: ZipError) -> std::io::Error {
+    match e {
+        ZipError::Io(e) => e,
+        _ => std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_read_header() {
+        let header = PckHeader::from_path(Path::new("testenv/GAMESTICK/Pong.pck")).unwrap();
+        assert_eq!(header.get_format(), 1);
+        assert_eq!(header.get_engine_version(), (3, 5, 1));
+        assert_eq!(header.get_file_count(), 19);
+        assert_eq!(header.get_fingerprint().is_some(), true);
+        assert_eq!(header.is_supported(), true);
+        // a different game has a different fingerprint
+        let other = PckHeader::from_path(Path::new("testenv/GAMESTICK/Super Platformer.pck")).unwrap();
+        assert_ne!(header.get_fingerprint(), other.get_fingerprint());
+        // a truncated file directory only loses the fingerprint
+        let data = std::fs::read("testenv/GAMESTICK/Pong.pck").unwrap();
+        let header = PckHeader::read(&mut &data[..120]).unwrap();
+        assert_eq!(header.get_engine_version(), (3, 5, 1));
+        assert_eq!(header.get_fingerprint(), None);
+    }
+
+    #[test]
+    fn ut_read_header_bad_magic() {
+        assert_eq!(PckHeader::from_path(Path::new("testenv/GAMESTICK/Pong.png")).is_err(), true);
+        assert_eq!(has_embedded_pack(Path::new("testenv/GAMESTICK/Pong.png")), false);
+        assert_eq!(PckHeader::from_path(Path::new("testenv/GAMESTICK/game")).is_err(), true);
+    }
+
+    #[test]
+    fn ut_read_exported_packs() {
+        let pck = PckHeader::from_path(Path::new("testenv/GAMESTICK/Pong.pck")).unwrap();
+        // the executable embeds the same pack
+        let exe = Path::new("testenv/exports/Pong.x86_64");
+        assert_eq!(PackKind::of(exe), PackKind::Embedded);
+        assert_eq!(has_embedded_pack(exe), true);
+        let header = PckHeader::from_path(exe).unwrap();
+        assert_eq!(header.kind, PackKind::Embedded);
+        assert_eq!(header.get_engine_version(), (3, 5, 1));
+        assert_eq!(header.get_fingerprint(), pck.get_fingerprint());
+
+        let zip = Path::new("testenv/exports/Pong.zip");
+        assert_eq!(PackKind::of(zip), PackKind::Zip);
+        let header = PckHeader::from_path(zip).unwrap();
+        assert_eq!(header.kind, PackKind::Zip);
+        assert_eq!(header.get_engine_version().0, 3);
+        assert_eq!(header.is_supported(), true);
+        // a zip without project settings is not a main pack
+        let e = PckHeader::from_path(Path::new("testenv/invalid/notes.zip")).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
\ No newline at end of file
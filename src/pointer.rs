@@ -0,0 +1,151 @@
+use std::process::Child;
+
+use iced::Point;
+
+/// Horizontal distance (in pixels) a press must travel before it is treated
+/// as a drag instead of a tap.
+const TAP_SLOP: f32 = 24.0;
+
+/// Horizontal distance (in pixels) a drag must travel to scroll the shelf by
+/// one game.
+const DRAG_STEP: f32 = 160.0;
+
+/// Tracks a mouse button or finger held against the screen to recognize taps,
+/// swipes, and drags across the game shelf.
+#[derive(Debug, PartialEq)]
+pub struct Pointer {
+    /// The latest known position of the cursor.
+    position: Point,
+    /// The position where the shelf was last scrolled during the current press.
+    anchor: Option<Point>,
+    /// Whether the current press has traveled far enough to be a drag.
+    dragging: bool,
+    /// Whether the current press has already scrolled the shelf.
+    scrolled: bool,
+}
+
+impl Pointer {
+    /// Creates a new [Pointer] with no press in progress.
+    pub fn new() -> Self {
+        Self {
+            position: Point::ORIGIN,
+            anchor: None,
+            dragging: false,
+            scrolled: false,
+        }
+    }
+
+    /// Begins a press at the position `position`.
+    pub fn press(&mut self, position: Point) {
+        self.position = position;
+        self.anchor = Some(position);
+        self.dragging = false;
+        self.scrolled = false;
+    }
+
+    /// Begins a press at the latest known cursor position.
+    pub fn press_here(&mut self) {
+        self.press(self.position)
+    }
+
+    /// Moves the pointer to `position`.
+    ///
+    /// Returns the number of games to scroll the shelf by, where positive values
+    /// scroll right. Dragging the shelf to the left reveals the games on the right.
+    pub fn moved(&mut self, position: Point) -> i32 {
+        self.position = position;
+        let anchor = match self.anchor {
+            Some(a) => a,
+            None => return 0,
+        };
+        let dx = position.x - anchor.x;
+        if dx.abs() > TAP_SLOP {
+            self.dragging = true;
+        }
+        let steps = (-dx / DRAG_STEP).trunc() as i32;
+        if steps != 0 {
+            self.anchor = Some(Point::new(anchor.x - steps as f32 * DRAG_STEP, anchor.y));
+            self.scrolled = true;
+        }
+        steps
+    }
+
+    /// Ends the current press at the position `position`.
+    ///
+    /// Returns the number of games to scroll the shelf by. A short swipe that
+    /// never reached a full drag step still scrolls by one game.
+    pub fn release(&mut self, position: Point) -> i32 {
+        let mut steps = self.moved(position);
+        if steps == 0 && self.dragging == true && self.scrolled == false {
+            let dx = position.x - self.anchor.unwrap_or(position).x;
+            steps = if dx < 0.0 { 1 } else { -1 };
+        }
+        self.anchor = None;
+        steps
+    }
+
+    /// Ends the current press at the latest known cursor position.
+    pub fn release_here(&mut self) -> i32 {
+        self.release(self.position)
+    }
+
+    /// Checks if the current press is a drag rather than a tap.
+    pub fn is_dragging(&self) -> bool {
+        self.anchor.is_some() && self.dragging == true
+    }
+}
+
+/// Spawns a background process to hide the mouse cursor after `seconds` of
+/// inactivity.
+///
+/// The `iced` crate cannot hide the cursor itself, so this relies on the
+/// `unclutter` utility for X11 being installed.
+pub fn hide_idle_cursor(seconds: u32) -> Option<Child> {
+    match std::process::Command::new("unclutter")
+        .arg("-idle")
+        .arg(seconds.to_string())
+        .arg("-root")
+        .spawn()
+    {
+        Ok(child) => Some(child),
+        Err(e) => {
+            log::error!("Failed to hide idle cursor with unclutter: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_tap_is_not_drag() {
+        let mut pointer = Pointer::new();
+        pointer.press(Point::new(100.0, 100.0));
+        assert_eq!(pointer.moved(Point::new(110.0, 104.0)), 0);
+        assert_eq!(pointer.is_dragging(), false);
+        assert_eq!(pointer.release(Point::new(110.0, 104.0)), 0);
+    }
+
+    #[test]
+    fn ut_swipe_scrolls_once() {
+        let mut pointer = Pointer::new();
+        pointer.press(Point::new(400.0, 100.0));
+        assert_eq!(pointer.moved(Point::new(340.0, 100.0)), 0);
+        assert_eq!(pointer.is_dragging(), true);
+        // swiping left reveals the next game on the right
+        assert_eq!(pointer.release(Point::new(330.0, 100.0)), 1);
+        assert_eq!(pointer.is_dragging(), false);
+    }
+
+    #[test]
+    fn ut_drag_scrolls_per_step() {
+        let mut pointer = Pointer::new();
+        pointer.press(Point::new(100.0, 100.0));
+        assert_eq!(pointer.moved(Point::new(100.0 + DRAG_STEP * 2.5, 100.0)), -2);
+        assert_eq!(pointer.moved(Point::new(100.0 + DRAG_STEP * 3.0, 100.0)), -1);
+        // the drag already scrolled so releasing does not scroll again
+        assert_eq!(pointer.release(Point::new(100.0 + DRAG_STEP * 3.1, 100.0)), 0);
+    }
+}
\ No newline at end of file
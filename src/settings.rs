@@ -0,0 +1,55 @@
+/// A console preference that can be changed from the settings menu.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Setting {
+    /// The colors, font, and artwork used to draw the console.
+    Theme,
+    /// The language of the console's messages.
+    Language,
+    /// Whether transitions are skipped.
+    ReduceMotion,
+}
+
+impl Setting {
+    /// Every setting in the order listed on the settings menu.
+    pub const ALL: [Setting; 3] = [Setting::Theme, Setting::Language, Setting::ReduceMotion];
+
+    /// The message catalog key for the label displayed next to the setting's value.
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::Theme => "setting-theme",
+            Self::Language => "setting-language",
+            Self::ReduceMotion => "setting-reduce-motion",
+        }
+    }
+}
+
+/// The settings menu opened over the current screen.
+#[derive(Debug, PartialEq)]
+pub struct Menu {
+    /// The index of the highlighted setting.
+    cursor: usize,
+}
+
+impl Menu {
+    /// Creates a new [Menu] with the first setting highlighted.
+    pub fn new() -> Self {
+        Self { cursor: 0 }
+    }
+
+    /// Highlights the previous setting, stopping at the first setting.
+    pub fn move_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Highlights the next setting, stopping at the last setting.
+    pub fn move_down(&mut self) {
+        if self.cursor + 1 < Setting::ALL.len() {
+            self.cursor += 1;
+        }
+    }
+
+    /// Accesses the highlighted setting.
+    pub fn get_selected(&self) -> Setting {
+        Setting::ALL[self.cursor]
+    }
+}
\ No newline at end of file
@@ -0,0 +1,167 @@
+use iced_native::event::{self, Event};
+use iced_native::layout::{self, Layout};
+use iced_native::mouse;
+use iced_native::overlay;
+use iced_native::renderer;
+use iced_native::touch;
+use iced_native::widget::tree::{self, Tree};
+use iced_native::widget::Widget;
+use iced_native::{Clipboard, Element, Length, Point, Rectangle, Shell};
+
+/// A widget that reports a click or tap on its content.
+///
+/// Unlike a button, the presses and releases are never captured, so they also
+/// reach the console's own tracking of drags and swipes across the shelf.
+pub struct Tap<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+    on_tap: Message,
+}
+
+/// Remembers whether the current press started on the content.
+#[derive(Debug, Default)]
+struct State {
+    pressed: bool,
+}
+
+impl<'a, Message, Renderer> Tap<'a, Message, Renderer> {
+    /// Creates a new [Tap] widget publishing `on_tap` when `content` is
+    /// clicked or tapped.
+    pub fn new(content: impl Into<Element<'a, Message, Renderer>>, on_tap: Message) -> Self {
+        Self {
+            content: content.into(),
+            on_tap: on_tap,
+        }
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for Tap<'a, Message, Renderer>
+where
+    Message: Clone,
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let status = self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event.clone(),
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        );
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_mut::<State>();
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                state.pressed = bounds.contains(cursor_position);
+            }
+            Event::Touch(touch::Event::FingerPressed { position, .. }) => {
+                state.pressed = bounds.contains(position);
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                if state.pressed == true && bounds.contains(cursor_position) == true {
+                    shell.publish(self.on_tap.clone());
+                }
+                state.pressed = false;
+            }
+            Event::Touch(touch::Event::FingerLifted { position, .. }) => {
+                if state.pressed == true && bounds.contains(position) == true {
+                    shell.publish(self.on_tap.clone());
+                }
+                state.pressed = false;
+            }
+            Event::Touch(touch::Event::FingerLost { .. }) => state.pressed = false,
+            _ => (),
+        }
+        // the press is left uncaptured for the console's pointer tracking
+        status
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content
+            .as_widget()
+            .draw(&tree.children[0], renderer, theme, style, layout, cursor_position, viewport)
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        match layout.bounds().contains(cursor_position) {
+            true => mouse::Interaction::Pointer,
+            false => self
+                .content
+                .as_widget()
+                .mouse_interaction(&tree.children[0], layout, cursor_position, viewport, renderer),
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        self.content.as_widget_mut().overlay(&mut tree.children[0], layout, renderer)
+    }
+}
+
+impl<'a, Message, Renderer> From<Tap<'a, Message, Renderer>> for Element<'a, Message, Renderer>
+where
+    Message: Clone + 'a,
+    Renderer: iced_native::Renderer + 'a,
+{
+    fn from(tap: Tap<'a, Message, Renderer>) -> Self {
+        Element::new(tap)
+    }
+}
@@ -0,0 +1,347 @@
+use std::path::{Path, PathBuf};
+
+use iced::theme::Palette;
+use iced::widget::container;
+use iced::{Color, Font, Theme};
+
+use crate::config::Config;
+
+/// The directory under `GOCO_ROOT` where theme files are stored.
+pub const THEMES_DIR: &str = "themes";
+
+/// The file extension for theme files.
+pub const THEME_EXT: &str = "cfg";
+
+/// The name of the theme used when none is selected.
+pub const DEFAULT_THEME: &str = "Dark";
+
+/// The border drawn around the selected game on the shelf.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Highlight {
+    color: Color,
+    width: f32,
+    radius: f32,
+}
+
+impl container::StyleSheet for Highlight {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            text_color: None,
+            background: None,
+            border_radius: self.radius,
+            border_width: self.width,
+            border_color: self.color,
+        }
+    }
+}
+
+/// A box drawn over the screen with a colored border, such as a notification.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Panel {
+    background: Color,
+    text: Color,
+    border: Color,
+}
+
+impl container::StyleSheet for Panel {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            text_color: Some(self.text),
+            background: Some(self.background.into()),
+            border_radius: 8.0,
+            border_width: 3.0,
+            border_color: self.border,
+        }
+    }
+}
+
+/// A filled circle of a single color, used to draw the loading spinner.
+#[derive(Debug, Clone, Copy)]
+pub struct Dot {
+    color: Color,
+    /// Half of the dot's size (in window pixels).
+    radius: f32,
+}
+
+impl Dot {
+    pub fn new(color: Color, radius: f32) -> Self {
+        Self {
+            color: color,
+            radius: radius,
+        }
+    }
+}
+
+impl container::StyleSheet for Dot {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            text_color: None,
+            background: Some(self.color.into()),
+            border_radius: self.radius,
+            border_width: 0.0,
+            border_color: Color::TRANSPARENT,
+        }
+    }
+}
+
+/// Reads a font file to be used by text widgets, such as a theme's or a
+/// language's font.
+///
+/// The font's data is kept alive for the rest of the program because the
+/// renderer requires a `'static` reference.
+pub(crate) fn load_font(path: &Path) -> Result<Font, String> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Font::External {
+            name: Box::leak(path.to_string_lossy().into_owned().into_boxed_str()),
+            bytes: Box::leak(bytes.into_boxed_slice()),
+        }),
+        Err(e) => Err(format!("{:?}: {}", path, e)),
+    }
+}
+
+/// The colors, font, and artwork used to draw the console.
+#[derive(Debug, Clone)]
+pub struct ConsoleTheme {
+    name: String,
+    palette: Palette,
+    font: Font,
+    /// Image drawn behind every screen.
+    background: Option<PathBuf>,
+    highlight: Highlight,
+    /// Image drawn on the insert screen.
+    insert_art: Option<PathBuf>,
+}
+
+impl ConsoleTheme {
+    /// Creates the built-in dark theme.
+    pub fn dark() -> Self {
+        let palette = Palette {
+            background: Color::from_rgb8(0x12, 0x12, 0x18),
+            text: Color::from_rgb8(0xec, 0xec, 0xec),
+            primary: Color::from_rgb8(0x4c, 0x8d, 0xff),
+            success: Color::from_rgb8(0x3b, 0xb2, 0x73),
+            danger: Color::from_rgb8(0xe5, 0x48, 0x4d),
+        };
+        Self {
+            name: String::from(DEFAULT_THEME),
+            palette: palette,
+            font: Font::Default,
+            background: None,
+            highlight: Highlight { color: palette.primary, width: 4.0, radius: 16.0 },
+            insert_art: None,
+        }
+    }
+
+    /// Creates the built-in light theme.
+    pub fn light() -> Self {
+        Self {
+            name: String::from("Light"),
+            palette: Palette::LIGHT,
+            highlight: Highlight { color: Palette::LIGHT.primary, width: 4.0, radius: 16.0 },
+            ..Self::dark()
+        }
+    }
+
+    /// Reads a theme from the file at `path`.
+    ///
+    /// Colors not set by the file are taken from the built-in dark theme. Paths
+    /// to fonts and images are relative to the file's directory.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let config = Config::load(path)?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let base = Self::dark();
+
+        let color = |key: &str, default: Color| -> Result<Color, String> {
+            match config.get("theme", key) {
+                Some(v) => Self::parse_color(v).ok_or(format!("{:?}: invalid color {:?} for {:?}", path, v, key)),
+                None => Ok(default),
+            }
+        };
+        let palette = Palette {
+            background: color("background-color", base.palette.background)?,
+            text: color("text-color", base.palette.text)?,
+            primary: color("primary-color", base.palette.primary)?,
+            success: color("success-color", base.palette.success)?,
+            danger: color("danger-color", base.palette.danger)?,
+        };
+        let highlight = Highlight {
+            color: match config.get("highlight", "color") {
+                Some(v) => Self::parse_color(v).ok_or(format!("{:?}: invalid highlight color {:?}", path, v))?,
+                None => palette.primary,
+            },
+            width: config.get("highlight", "width").and_then(|v| v.parse().ok()).unwrap_or(base.highlight.width),
+            radius: config.get("highlight", "radius").and_then(|v| v.parse().ok()).unwrap_or(base.highlight.radius),
+        };
+        let font = match config.get("theme", "font") {
+            Some(f) => load_font(&dir.join(f))?,
+            None => Font::Default,
+        };
+
+        Ok(Self {
+            name: match config.get("theme", "name") {
+                Some(n) => n.to_string(),
+                None => path.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+            },
+            palette: palette,
+            font: font,
+            background: config.get("theme", "background-image").map(|p| dir.join(p)),
+            highlight: highlight,
+            insert_art: config.get("theme", "insert-art").map(|p| dir.join(p)),
+        })
+    }
+
+    /// Converts a hex color code written as `#rrggbb` or `#rrggbbaa`.
+    pub fn parse_color(code: &str) -> Option<Color> {
+        let hex = code.trim().strip_prefix('#')?;
+        if hex.is_ascii() == false {
+            return None;
+        }
+        let byte = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        match hex.len() {
+            6 => Some(Color::from_rgb8(byte(0)?, byte(2)?, byte(4)?)),
+            8 => Some(Color::from_rgba8(byte(0)?, byte(2)?, byte(4)?, byte(6)? as f32 / 255.0)),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    pub fn get_font(&self) -> Font {
+        self.font
+    }
+
+    pub fn get_background(&self) -> Option<&PathBuf> {
+        self.background.as_ref()
+    }
+
+    pub fn get_highlight(&self) -> Highlight {
+        self.highlight
+    }
+
+    pub fn get_insert_art(&self) -> Option<&PathBuf> {
+        self.insert_art.as_ref()
+    }
+
+    /// Creates a [Panel] in the theme's colors with a border of the color `accent`.
+    pub fn get_panel(&self, accent: Color) -> Panel {
+        Panel {
+            background: self.palette.background,
+            text: self.palette.text,
+            border: accent,
+        }
+    }
+
+    /// Creates the `iced` theme for drawing widgets with the theme's palette.
+    pub fn to_theme(&self) -> Theme {
+        Theme::custom(self.palette)
+    }
+}
+
+/// The collection of available themes and the one currently in use.
+#[derive(Debug, Clone)]
+pub struct Themes {
+    list: Vec<ConsoleTheme>,
+    current: usize,
+}
+
+impl Themes {
+    /// Loads the built-in themes followed by every theme file found in `dir`,
+    /// and selects the theme named `selected` if it exists.
+    pub fn load(dir: &Path, selected: Option<&str>) -> Self {
+        let mut list = vec![ConsoleTheme::dark(), ConsoleTheme::light()];
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().map_or(false, |ext| ext == THEME_EXT))
+                .collect();
+            paths.sort();
+            for path in paths {
+                match ConsoleTheme::load(&path) {
+                    Ok(theme) => list.push(theme),
+                    Err(e) => log::error!("Failed to load theme: {}", e),
+                }
+            }
+        }
+        let mut themes = Self { list: list, current: 0 };
+        if let Some(name) = selected {
+            themes.select(name);
+        }
+        themes
+    }
+
+    /// Switches to the theme named `name`.
+    ///
+    /// Returns `false` if no theme has the name.
+    pub fn select(&mut self, name: &str) -> bool {
+        match self.list.iter().position(|t| t.get_name().eq_ignore_ascii_case(name)) {
+            Some(i) => {
+                self.current = i;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Switches to the next theme in the list, or the previous one when `forward`
+    /// is `false`. The list wraps around at both ends.
+    pub fn cycle(&mut self, forward: bool) {
+        let len = self.list.len();
+        self.current = match forward {
+            true => (self.current + 1) % len,
+            false => (self.current + len - 1) % len,
+        };
+    }
+
+    /// References the theme currently in use.
+    pub fn get_current(&self) -> &ConsoleTheme {
+        &self.list[self.current]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_parse_color() {
+        assert_eq!(ConsoleTheme::parse_color("#ff8000"), Some(Color::from_rgb8(255, 128, 0)));
+        assert_eq!(ConsoleTheme::parse_color("#00000080").map(|c| (c.a * 255.0).round() as u8), Some(128));
+        assert_eq!(ConsoleTheme::parse_color("ff8000"), None);
+        assert_eq!(ConsoleTheme::parse_color("#ff80"), None);
+        assert_eq!(ConsoleTheme::parse_color("#gg8000"), None);
+    }
+
+    #[test]
+    fn ut_load_theme_file() {
+        let theme = ConsoleTheme::load(Path::new("testenv/themes/ocean.cfg")).unwrap();
+        assert_eq!(theme.get_name(), "Ocean");
+        assert_eq!(theme.get_palette().background, Color::from_rgb8(0x0b, 0x1d, 0x33));
+        // unset colors fall back to the dark theme
+        assert_eq!(theme.get_palette().danger, ConsoleTheme::dark().get_palette().danger);
+        assert_eq!(theme.get_background(), Some(&PathBuf::from("testenv/themes/ocean/waves.png")));
+        assert_eq!(theme.get_highlight().width, 6.0);
+    }
+
+    #[test]
+    fn ut_cycle_themes() {
+        let mut themes = Themes::load(Path::new("testenv/themes"), Some("ocean"));
+        assert_eq!(themes.get_current().get_name(), "Ocean");
+        themes.cycle(true);
+        assert_eq!(themes.get_current().get_name(), DEFAULT_THEME);
+        themes.cycle(false);
+        assert_eq!(themes.get_current().get_name(), "Ocean");
+        assert_eq!(themes.select("missing"), false);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,232 @@
+use std::time::{Duration, Instant};
+
+/// The time between each step of a running transition.
+pub const TWEEN_TICK: Duration = Duration::from_millis(16);
+
+/// The time for the shelf to slide to the next game.
+const SLIDE_TIME: Duration = Duration::from_millis(250);
+
+/// The time for the selected game's icon to grow to full size.
+const ZOOM_TIME: Duration = Duration::from_millis(200);
+
+/// The time for the game titles to fade in.
+const FADE_TIME: Duration = Duration::from_millis(300);
+
+/// A curve shaping how a value moves between its start and end.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Easing {
+    /// Moves at a constant speed.
+    Linear,
+    /// Starts fast and slows down.
+    EaseOut,
+    /// Starts slow, speeds up, and slows down again.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps the fraction of time passed `t` (from 0 to 1) to the fraction of
+    /// distance travelled.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut => match t < 0.5 {
+                true => 4.0 * t * t * t,
+                false => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            },
+        }
+    }
+}
+
+/// A value moving from one number to another over a set time.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Tween {
+    from: f32,
+    to: f32,
+    duration: Duration,
+    elapsed: Duration,
+    easing: Easing,
+}
+
+impl Tween {
+    /// Creates a new [Tween] starting at `from`.
+    pub fn new(from: f32, to: f32, duration: Duration, easing: Easing) -> Self {
+        Self {
+            from: from,
+            to: to,
+            duration: duration,
+            elapsed: Duration::ZERO,
+            easing: easing,
+        }
+    }
+
+    /// Creates a [Tween] that has already finished at `value`.
+    pub fn fixed(value: f32) -> Self {
+        Self::new(value, value, Duration::ZERO, Easing::Linear)
+    }
+
+    /// Moves the tween forward by `delta`.
+    pub fn advance(&mut self, delta: Duration) {
+        self.elapsed = (self.elapsed + delta).min(self.duration);
+    }
+
+    /// Jumps to the end of the tween.
+    pub fn skip(&mut self) {
+        self.elapsed = self.duration;
+    }
+
+    /// Computes the tween's value at its current time.
+    pub fn get_value(&self) -> f32 {
+        if self.is_finished() == true {
+            return self.to;
+        }
+        let t = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        self.from + (self.to - self.from) * self.easing.apply(t)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+/// The transitions played on the library shelf after moving to another game.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShelfMotion {
+    /// The shelf's offset from its resting place (in slots).
+    slide: Tween,
+    /// The selected icon's progress toward full size (from 0 to 1).
+    zoom: Tween,
+    /// The titles' opacity (from 0 to 1).
+    fade: Tween,
+    /// Jumps straight to the end of every transition when set.
+    reduced: bool,
+    /// The time of the previous tick.
+    last: Option<Instant>,
+}
+
+impl ShelfMotion {
+    /// Creates a new [ShelfMotion] resting on the selected game.
+    pub fn new(reduced: bool) -> Self {
+        Self {
+            slide: Tween::fixed(0.0),
+            zoom: Tween::fixed(1.0),
+            fade: Tween::fixed(1.0),
+            reduced: reduced,
+            last: None,
+        }
+    }
+
+    /// Starts the transitions after the shelf moved one game to the right (for
+    /// positive `direction`) or left (for negative `direction`).
+    ///
+    /// Moving again before the previous transitions end skips to the end instead
+    /// of starting over, so holding a direction scrolls without delay.
+    pub fn start(&mut self, direction: f32) {
+        if self.reduced == true || self.is_animating() == true {
+            return self.skip();
+        }
+        self.slide = Tween::new(direction.signum(), 0.0, SLIDE_TIME, Easing::EaseOut);
+        self.zoom = Tween::new(0.0, 1.0, ZOOM_TIME, Easing::EaseOut);
+        self.fade = Tween::new(0.0, 1.0, FADE_TIME, Easing::EaseInOut);
+        self.last = None;
+    }
+
+    /// Updates the transitions to the time `now`.
+    pub fn tick(&mut self, now: Instant) {
+        let delta = match self.last {
+            Some(last) => now.saturating_duration_since(last),
+            None => Duration::ZERO,
+        };
+        self.last = Some(now);
+        for tween in [&mut self.slide, &mut self.zoom, &mut self.fade] {
+            tween.advance(delta);
+        }
+    }
+
+    /// Jumps to the end of every transition.
+    pub fn skip(&mut self) {
+        for tween in [&mut self.slide, &mut self.zoom, &mut self.fade] {
+            tween.skip();
+        }
+    }
+
+    /// Enables or disables the transitions. Disabling them ends any running
+    /// transitions.
+    pub fn set_reduced(&mut self, reduced: bool) {
+        self.reduced = reduced;
+        if reduced == true {
+            self.skip();
+        }
+    }
+
+    pub fn is_reduced(&self) -> bool {
+        self.reduced
+    }
+
+    /// Checks if any transition is still running.
+    pub fn is_animating(&self) -> bool {
+        [self.slide, self.zoom, self.fade].iter().any(|t| t.is_finished() == false)
+    }
+
+    pub fn get_slide(&self) -> f32 {
+        self.slide.get_value()
+    }
+
+    pub fn get_zoom(&self) -> f32 {
+        self.zoom.get_value()
+    }
+
+    pub fn get_fade(&self) -> f32 {
+        self.fade.get_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_easing_endpoints() {
+        for easing in [Easing::Linear, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert_eq!(Easing::EaseOut.apply(0.5) > Easing::Linear.apply(0.5), true);
+        assert_eq!(Easing::EaseInOut.apply(0.25) < Easing::Linear.apply(0.25), true);
+    }
+
+    #[test]
+    fn ut_tween_progress() {
+        let mut tween = Tween::new(10.0, 20.0, Duration::from_millis(100), Easing::Linear);
+        tween.advance(Duration::from_millis(50));
+        assert_eq!(tween.get_value(), 15.0);
+        tween.advance(Duration::from_millis(500));
+        assert_eq!((tween.get_value(), tween.is_finished()), (20.0, true));
+        assert_eq!(Tween::fixed(3.0).is_finished(), true);
+    }
+
+    #[test]
+    fn ut_shelf_motion() {
+        let now = Instant::now();
+        let mut motion = ShelfMotion::new(false);
+        motion.start(1.0);
+        motion.tick(now);
+        assert_eq!((motion.get_slide(), motion.get_zoom(), motion.get_fade()), (1.0, 0.0, 0.0));
+        motion.tick(now + SLIDE_TIME);
+        assert_eq!(motion.get_slide(), 0.0);
+        assert_eq!(motion.is_animating(), true);
+        // rapid input skips to the end
+        motion.start(-1.0);
+        assert_eq!(motion.is_animating(), false);
+        motion.start(-1.0);
+        assert_eq!(motion.get_slide(), -1.0);
+        // reduced motion never animates
+        motion.set_reduced(true);
+        assert_eq!(motion.is_animating(), false);
+        motion.start(1.0);
+        assert_eq!((motion.get_slide(), motion.get_fade()), (0.0, 1.0));
+    }
+}
@@ -0,0 +1,161 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+// @note: Only Linux can watch for changes (with inotify). Elsewhere the library
+// is only read again when a source is inserted or the player refreshes it.
+
+// the ids of the sources watched by a watcher thread
+#[cfg(target_os = "linux")]
+static WATCHED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+// global queue to be filled by the watcher threads and drained by the main goco process
+static CHANGED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Spawns a background thread that watches every directory under `root` for
+/// files being added, changed or removed, queueing the source's `id` to be
+/// collected with [drain]. Does nothing if the source is already watched.
+///
+/// The thread stops once `root` is removed or its drive is unmounted.
+#[cfg(target_os = "linux")]
+pub fn watch(id: &str, root: &Path) {
+    let mut watched = WATCHED.lock().unwrap();
+    if watched.iter().any(|w| w == id) == true {
+        return;
+    }
+    watched.push(id.to_string());
+    let (id, root) = (id.to_string(), root.to_path_buf());
+    std::thread::spawn(move || {
+        log::debug!("Watching {:?} for changes ...", root);
+        if let Err(e) = watch_tree(&id, &root) {
+            log::warn!("Failed to watch {:?} for changes: {}", root, e);
+        }
+        log::debug!("Stopped watching {:?}", root);
+        // allow the source to be watched again when it is reinserted
+        WATCHED.lock().unwrap().retain(|w| w != &id);
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn watch(_id: &str, _root: &Path) {}
+
+/// Removes and returns the id of every source whose files changed since the last call.
+pub fn drain() -> Vec<String> {
+    match CHANGED.lock() {
+        Ok(mut queue) => queue.drain(..).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Queues the source with the id `id` to be read again, unless it already is.
+#[cfg(target_os = "linux")]
+fn raise(id: &str) {
+    let mut changed = CHANGED.lock().unwrap();
+    if changed.iter().any(|c| c == id) == false {
+        changed.push(id.to_string());
+    }
+}
+
+/// Reads changes to the directories under `root` until `root` goes away.
+#[cfg(target_os = "linux")]
+fn watch_tree(id: &str, root: &Path) -> std::io::Result<()> {
+    use inotify::{EventMask, Inotify, WatchMask};
+    use std::collections::HashMap;
+
+    let mask = WatchMask::CREATE
+        | WatchMask::DELETE
+        | WatchMask::CLOSE_WRITE
+        | WatchMask::MOVED_FROM
+        | WatchMask::MOVED_TO
+        | WatchMask::DELETE_SELF
+        | WatchMask::MOVE_SELF;
+    let mut inotify = Inotify::init()?;
+    let mut dirs = HashMap::new();
+    let mut pending = vec![root.to_path_buf()];
+    let mut buffer = [0; 4096];
+    loop {
+        // watch the directories found so far, including their own subdirectories
+        while let Some(dir) = pending.pop() {
+            match inotify.watches().add(&dir, mask) {
+                Ok(wd) => {
+                    if let Ok(entries) = std::fs::read_dir(&dir) {
+                        pending.extend(entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()));
+                    }
+                    dirs.insert(wd, dir);
+                }
+                // the root must be watched, but a subdirectory may already be gone
+                Err(e) if dir == root => return Err(e),
+                Err(_) => (),
+            }
+        }
+        let mut changed = false;
+        for event in inotify.read_events_blocking(&mut buffer)? {
+            if event.mask.contains(EventMask::Q_OVERFLOW) == true {
+                changed = true;
+                continue;
+            }
+            let dir = match dirs.get(&event.wd) {
+                Some(d) => d.clone(),
+                None => continue,
+            };
+            // the drive was unmounted or the root itself was removed
+            if event.mask.contains(EventMask::IGNORED) == true {
+                dirs.remove(&event.wd);
+                match dir == root {
+                    true => return Ok(()),
+                    false => continue,
+                }
+            }
+            if event.mask.contains(EventMask::UNMOUNT) == true {
+                return Ok(());
+            }
+            if let Some(name) = event.name {
+                if event.mask.contains(EventMask::ISDIR) == true
+                    && event.mask.intersects(EventMask::CREATE | EventMask::MOVED_TO) == true
+                {
+                    pending.push(dir.join(name));
+                }
+                changed = true;
+            }
+        }
+        if changed == true {
+            raise(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn ut_watch_new_pack() {
+        let root = std::env::temp_dir().join(format!("goco-watch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("new")).unwrap();
+        let id = format!("dir:{}", root.display());
+        watch(&id, &root);
+        // give the thread time to watch the directories
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        std::fs::copy("testenv/GAMESTICK/Pong.pck", root.join("new/Pong.pck")).unwrap();
+
+        let mut changed = Vec::new();
+        for _ in 0..20 {
+            changed = drain();
+            if changed.is_empty() == false {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        assert_eq!(changed, vec![id.clone()]);
+        // removing the root stops the watcher
+        std::fs::remove_dir_all(&root).unwrap();
+        for _ in 0..20 {
+            if WATCHED.lock().unwrap().contains(&id) == false {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        assert_eq!(WATCHED.lock().unwrap().contains(&id), false);
+    }
+}
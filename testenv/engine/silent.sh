@@ -0,0 +1,3 @@
+#!/bin/sh
+# Runs without printing anything, like an engine that never starts the game.
+sleep 5
@@ -0,0 +1,4 @@
+#!/bin/sh
+# Prints a version line like the Godot engine does on startup, then exits.
+echo "Godot Engine v3.5.1.stable.official"
+sleep 1